@@ -1,23 +1,12 @@
 label .ENTRY
     print "Enter number 1: "
-    input
-    var n1 TEMP
-    to_number n1
-    var n1 TEMP
+    input -> n1
+    to_number n1 -> n1
 
     print "Enter number 2: "
-    input
-    var n2 TEMP
-    to_number n2
-    var n2 TEMP
+    input -> n2
+    to_number n2 -> n2
 
-    add n1 n2
-    var sum TEMP
-    to_string sum
+    add n1 n2 -> sum
 
-    print "The sum of "
-    print n1
-    print " and "
-    print n2
-    print " is "
-    print sum
\ No newline at end of file
+    print "The sum of {n1} and {n2} is {sum}"
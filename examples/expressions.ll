@@ -0,0 +1,15 @@
+label .ENTRY
+    var price 12
+    var tax 3
+    var count 2
+
+    comment `let` evaluates the expression and stores it to `total`
+    let total = (price + tax) * count
+    let message = "Total: " + total
+    print message
+    print_newline
+
+    comment Commands that store a value to `TEMP` can be called like functions
+    let is_even = mod(total, 2) == 0 and total > 10
+    print is_even
+    print_newline
@@ -0,0 +1,25 @@
+label .ENTRY
+    call greet "Bob" "Hi"
+
+    call factorial 5 -> result
+    println "5! = {result}"
+
+    let area = square(4) * 2
+    println "Two squares of side 4 cover {area}"
+
+label greet name greeting
+    println "{greeting}, {name}!"
+
+label factorial n
+    jmp_le n 1 factorial_base
+    sub n 1 -> smaller
+    call factorial smaller -> smaller_factorial
+    mul n smaller_factorial -> product
+    return product
+
+label factorial_base
+    return 1
+
+label square side
+    mul side side -> product
+    return product
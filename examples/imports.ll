@@ -0,0 +1,8 @@
+import "lib/greetings.ll"
+
+label .ENTRY
+    print "What is your name? "
+    input -> name
+
+    jmp greetings.hello
+    jmp greetings.goodbye
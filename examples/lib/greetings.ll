@@ -0,0 +1,12 @@
+comment Labels used by examples/imports.ll as `greetings.hello` and `greetings.goodbye`
+
+label hello
+    println "Hello, {name}!"
+    jmp divider
+
+label goodbye
+    println "Goodbye, {name}!"
+    jmp divider
+
+label divider
+    println "----------"
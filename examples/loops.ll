@@ -0,0 +1,18 @@
+label .ENTRY
+    comment `range` stores a list of numbers to `TEMP`, the end is not included
+    range 1 20
+    var numbers TEMP
+
+    comment `foreach` runs the label `print_odd` for every number, storing it to `n`
+    foreach n numbers print_odd
+    print_newline
+
+label print_odd
+    mod n 2
+    comment `continue` skips to the next number, `break` would stop the loop
+    jmp_eq TEMP 0 skip
+    print n
+    print " "
+
+label skip
+    continue
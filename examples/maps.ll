@@ -0,0 +1,21 @@
+label .ENTRY
+    comment `map_new` stores an empty map to `TEMP`
+    map_new
+    var person TEMP
+
+    comment `map_set` changes the map stored in the variable itself
+    map_set person "name" "John"
+    map_set person "age" 21
+
+    comment Maps are printed in the order their keys were added
+    print person
+    print_newline
+
+    comment The third argument of `map_get` is used if the key does not exist
+    map_get person "city" "Unknown"
+    print TEMP
+    print_newline
+
+    map_keys person
+    print TEMP
+    print_newline
@@ -2,6 +2,7 @@ label .ENTRY
 	is_match "Hello World This Is An Unironic Sentence" "[A-Z]"
 	print TEMP
 	print_newline
+	jmp_if TEMP .HAS_CAPITALS
 
 	count_matches "Hello World This Is An Unironic Sentence" "[A-Z]"
 	print TEMP
@@ -13,4 +14,8 @@ label .ENTRY
 	
 	replace_n "Hello World This Is An Unironic Sentence" "[A-Z]" "" 2
 	print TEMP
-	print_newline
\ No newline at end of file
+	print_newline
+
+label .HAS_CAPITALS
+	print "The sentence has capital letters."
+	print_newline
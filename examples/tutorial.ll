@@ -34,9 +34,9 @@ label can_drive
     comment jmp_gt is a command which jumps to a label if the first value is greater than the second value.
     comment In this case, if `age` is greater than 17, then jump to `print_can_drive_1`
     jmp_gt age 17 _can_drive_1
-    comment jmp_gt is a command which jumps to a label if the first value is less than the second value.
-    comment In this case, if `age` is less than 17, then jump to `print_can_drive_0`
-    jmp_lt age 17 _can_drive_0
+    comment jmp_le is a command which jumps to a label if the first value is less than or equal to the second value.
+    comment In this case, if `age` is less than or equal to 17, then jump to `print_can_drive_0`
+    jmp_le age 17 _can_drive_0
 
 label _can_drive_1
     comment Return 1 if the person can drive
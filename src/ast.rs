@@ -2,6 +2,10 @@
 pub enum Types {
     String,
     Number,
+    Bool,
+    List,
+    Map,
+    Nil,
     Identifier,
     Unknown,
 }
@@ -11,3 +15,13 @@ impl std::fmt::Display for Types {
         write!(f, "{:?}", self)
     }
 }
+
+/// What `interpret` should do after running a block of code.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Flow {
+    Next,
+    Break,
+    Continue,
+    // Leaves the label being run, its value is in `TEMP`
+    Return,
+}
@@ -0,0 +1,252 @@
+use crate::errors::Error;
+
+// Lists and maps are stored in the variable table like every other value, as
+// text. A list looks like `["a", 1]` and a map like `{"name": "John", "age": 21}`.
+// Strings nested inside a collection have their `"` and `\` escaped so the
+// text can be split back into elements without ambiguity.
+
+fn escape(content: &str) -> String {
+    content.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(content: &str) -> String {
+    let mut result = String::new();
+    let mut characters = content.chars();
+
+    while let Some(c) = characters.next() {
+        if c == '\\' {
+            if let Some(next) = characters.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+fn is_string(value: &str) -> bool {
+    value.len() >= 2 && value.starts_with('"') && value.ends_with('"')
+}
+
+/// Converts a value as stored in a variable into its form inside a collection.
+pub fn encode_element(value: &str) -> String {
+    if is_string(value) {
+        "\"".to_owned() + &escape(&value[1..value.len() - 1]) + "\""
+    } else {
+        value.to_string()
+    }
+}
+
+/// Converts an element of a collection back into a value that can be stored in a variable.
+pub fn decode_element(element: &str) -> String {
+    if is_string(element) {
+        "\"".to_owned() + &unescape(&element[1..element.len() - 1]) + "\""
+    } else {
+        element.to_string()
+    }
+}
+
+/// Splits the inside of a collection on `separator`, ignoring separators that
+/// appear inside strings or nested collections.
+fn split_top_level(inner: &str, separator: char) -> Result<Vec<String>, Error> {
+    let mut parts: Vec<String> = Vec::new();
+    let mut temp: String = String::new();
+    let mut depth: usize = 0;
+    let mut is_string: bool = false;
+    let mut is_escaped: bool = false;
+
+    for c in inner.chars() {
+        if is_string {
+            temp.push(c);
+            if is_escaped {
+                is_escaped = false;
+            } else if c == '\\' {
+                is_escaped = true;
+            } else if c == '"' {
+                is_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                is_string = true;
+                temp.push(c);
+            }
+
+            '[' | '{' => {
+                depth += 1;
+                temp.push(c);
+            }
+
+            ']' | '}' => {
+                if depth == 0 {
+                    return Err(Error::RuntimeError(format!(
+                        "Unexpected `{}` in `{}`.",
+                        c, inner
+                    )));
+                }
+                depth -= 1;
+                temp.push(c);
+            }
+
+            _ if c == separator && depth == 0 => {
+                parts.push(temp.trim().to_string());
+                temp = String::new();
+            }
+
+            _ => temp.push(c),
+        }
+    }
+
+    if is_string || depth != 0 {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is not terminated.",
+            inner
+        )));
+    }
+
+    if !temp.trim().is_empty() || !parts.is_empty() {
+        parts.push(temp.trim().to_string());
+    }
+
+    Ok(parts)
+}
+
+/// Returns the values held by the list `list`.
+pub fn parse_list(list: &str) -> Result<Vec<String>, Error> {
+    if !(list.starts_with('[') && list.ends_with(']')) {
+        return Err(Error::RuntimeError(format!("`{}` is not a list.", list)));
+    }
+
+    Ok(split_top_level(&list[1..list.len() - 1], ',')?
+        .iter()
+        .map(|element| decode_element(element))
+        .collect())
+}
+
+/// Builds a list out of `values`.
+pub fn make_list(values: &[String]) -> String {
+    "[".to_owned()
+        + &values
+            .iter()
+            .map(|value| encode_element(value))
+            .collect::<Vec<String>>()
+            .join(", ")
+        + "]"
+}
+
+/// Returns the key-value pairs held by the map `map` in insertion order.
+pub fn parse_map(map: &str) -> Result<Vec<(String, String)>, Error> {
+    if !(map.starts_with('{') && map.ends_with('}')) {
+        return Err(Error::RuntimeError(format!("`{}` is not a map.", map)));
+    }
+
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for entry in split_top_level(&map[1..map.len() - 1], ',')? {
+        let key_and_value = split_top_level(&entry, ':')?;
+
+        if key_and_value.len() != 2 {
+            return Err(Error::RuntimeError(format!(
+                "`{}` is not a valid map entry.",
+                entry
+            )));
+        }
+
+        entries.push((
+            decode_element(&key_and_value[0]),
+            decode_element(&key_and_value[1]),
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// Builds a map out of `entries`, keeping their order.
+pub fn make_map(entries: &[(String, String)]) -> String {
+    "{".to_owned()
+        + &entries
+            .iter()
+            .map(|(key, value)| encode_element(key) + ": " + &encode_element(value))
+            .collect::<Vec<String>>()
+            .join(", ")
+        + "}"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(content: &str) -> String {
+        "\"".to_owned() + content + "\""
+    }
+
+    #[test]
+    fn list_round_trip() {
+        let values = vec![
+            string("a, b"),
+            string("say \"hi\""),
+            string("C:\\temp\\"),
+            "1.5".to_string(),
+            "[1, 2]".to_string(),
+            string(""),
+        ];
+
+        let list = make_list(&values);
+        assert_eq!(
+            list,
+            r#"["a, b", "say \"hi\"", "C:\\temp\\", 1.5, [1, 2], ""]"#
+        );
+        assert_eq!(parse_list(&list), Ok(values));
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let entries = vec![
+            (string("a:b"), string("x, y")),
+            (string("q\"uote"), "true".to_string()),
+            (string("back\\slash"), "{\"n\": [1, 2]}".to_string()),
+            (string(",:\"\\"), string("}")),
+        ];
+
+        let map = make_map(&entries);
+        assert_eq!(parse_map(&map), Ok(entries));
+    }
+
+    #[test]
+    fn empty_collections() {
+        assert_eq!(make_list(&[]), "[]");
+        assert_eq!(parse_list("[]"), Ok(Vec::new()));
+        assert_eq!(make_map(&[]), "{}");
+        assert_eq!(parse_map("{}"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn invalid_collections() {
+        assert_eq!(
+            parse_list("[1, \"a]"),
+            Err(Error::RuntimeError(
+                "`1, \"a` is not terminated.".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_list("[1, 2]]"),
+            Err(Error::RuntimeError(
+                "Unexpected `]` in `1, 2]`.".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_map("{\"a\" 1}"),
+            Err(Error::RuntimeError(
+                "`\"a\" 1` is not a valid map entry.".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_map("[1]"),
+            Err(Error::RuntimeError("`[1]` is not a map.".to_string()))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::errors::Error;
+use crate::strings::to_string_value;
+use crate::{ast, lexer};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::Path;
+
+// The names of the variables that cannot be changed.
+thread_local! {
+    static CONSTANTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// The constants that every script has.
+const PREDEFINED: [&str; 6] = ["OS", "ARCH", "SCRIPT_PATH", "SCRIPT_DIR", "PID", "VERSION"];
+
+fn define(name: &str, value: String, variables: &mut HashMap<String, String>) {
+    CONSTANTS.with(|constants| constants.borrow_mut().insert(name.to_string()));
+    variables.insert(name.to_string(), value);
+}
+
+/// Creates the predefined constants for the script at `script_path`.
+pub fn define_predefined(script_path: &Path, variables: &mut HashMap<String, String>) {
+    let script_path = script_path
+        .canonicalize()
+        .unwrap_or_else(|_| script_path.to_path_buf());
+    let script_dir = script_path.parent().unwrap_or_else(|| Path::new(""));
+
+    define("OS", to_string_value(env::consts::OS), variables);
+    define("ARCH", to_string_value(env::consts::ARCH), variables);
+    define(
+        "SCRIPT_PATH",
+        to_string_value(&script_path.display().to_string()),
+        variables,
+    );
+    define(
+        "SCRIPT_DIR",
+        to_string_value(&script_dir.display().to_string()),
+        variables,
+    );
+    define("PID", std::process::id().to_string(), variables);
+    define(
+        "VERSION",
+        to_string_value(env!("CARGO_PKG_VERSION")),
+        variables,
+    );
+}
+
+pub fn is_constant(name: &str) -> bool {
+    CONSTANTS.with(|constants| constants.borrow().contains(name))
+}
+
+/// The names of the variables that running `line` gives a value to.
+pub fn assigned_variables(line: &[String]) -> Vec<String> {
+    let args = &line[1..];
+    let mut assigned: Vec<String> = Vec::new();
+
+    // `<COMMAND> <ARGS> -> <VARIABLE_NAME>`
+    if args.len() >= 2 && args[args.len() - 2] == "->" {
+        assigned.push(args[args.len() - 1].clone());
+    }
+
+    match line[0].as_str() {
+        "var" | "local" | "let" | "const" | "alias" | "unset" | "foreach" | "map_set"
+        | "map_remove"
+            if !args.is_empty() =>
+        {
+            assigned.push(args[0].clone())
+        }
+
+        "global" if args.len() == 2 => assigned.push(args[0].clone()),
+
+        // The parameters of a label get the values it is called with
+        "label" if !args.is_empty() => assigned.extend(args[1..].iter().cloned()),
+
+        _ => {}
+    }
+
+    assigned
+}
+
+/// Checks, before the script runs, that no line changes a constant, and that no constant is
+/// created twice. On failure, returns the label, line number and line along with the problem.
+pub fn check_labels(
+    labels: &HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> Result<(), (String, usize, String, Error)> {
+    let mut lines: Vec<(&String, &(usize, lexer::Line))> = labels
+        .iter()
+        .flat_map(|(label_name, code)| code.iter().map(move |line| (label_name, line)))
+        .collect();
+    lines.sort_by_key(|(_, (line_number, _))| *line_number);
+
+    let mut constants: HashSet<String> = PREDEFINED.iter().map(|name| name.to_string()).collect();
+    let mut declared: HashSet<String> = HashSet::new();
+
+    for (_, (_, lexer::Line(line))) in lines.iter() {
+        if line[0] == "const" && line.len() > 1 {
+            constants.insert(line[1].clone());
+        }
+    }
+
+    for (label_name, (line_number, lexer::Line(line))) in lines {
+        for name in assigned_variables(line) {
+            if !constants.contains(&name) {
+                continue;
+            }
+
+            let is_declaration = line[0] == "const" && line[1] == name;
+
+            let problem = if !is_declaration || PREDEFINED.contains(&name.as_str()) {
+                format!("Cannot change `{}` as it is a constant.", name)
+            } else if !declared.insert(name.clone()) {
+                format!("Constant `{}` is created more than once.", name)
+            } else {
+                continue;
+            };
+
+            return Err((
+                label_name.clone(),
+                *line_number,
+                line.join(" "),
+                Error::RuntimeError(problem),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `const`.
+pub fn command(args: &[String], variables: &mut HashMap<String, String>) -> Result<(), Error> {
+    crate::expect_args(args, 2)?;
+    let name = args[0].clone();
+
+    if crate::get_type(name.clone()) != ast::Types::Identifier || name == "TEMP" {
+        return Err(Error::RuntimeError(format!(
+            "`{}` cannot be the name of a constant.",
+            name
+        )));
+    }
+
+    if is_constant(&name) {
+        return Err(Error::RuntimeError(format!(
+            "Constant `{}` already exists.",
+            name
+        )));
+    }
+
+    let (value, _) = crate::resolve_value(args[1].clone(), variables)?;
+    define(&name, value, variables);
+
+    Ok(())
+}
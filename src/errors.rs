@@ -1,5 +1,18 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, Write};
 use std::process;
 
+thread_local! {
+    // The script that each label of an imported script comes from
+    static LABEL_FILES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+
+    // The script of every label that is running, the last one being the innermost, which is
+    // `None` for the script that is run
+    static RUNNING_FILES: RefCell<Vec<Option<String>>> = const { RefCell::new(Vec::new()) };
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(PartialEq, Debug, Clone)]
 pub enum Error {
     None,
@@ -9,11 +22,41 @@ pub enum Error {
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::None => write!(f, "None"),
+            Error::LexingError(msg) | Error::RuntimeError(msg) => write!(f, "{}", msg),
+        }
     }
 }
 
+/// Records that label `label_name` comes from the imported script at `path`.
+pub fn set_label_file(label_name: &str, path: &str) {
+    LABEL_FILES.with(|files| {
+        files
+            .borrow_mut()
+            .insert(label_name.to_string(), path.to_string())
+    });
+}
+
+/// Starts running label `label_name`, so that errors say which script it is in.
+pub fn enter_label(label_name: &str) {
+    let file = LABEL_FILES.with(|files| files.borrow().get(label_name).cloned());
+    RUNNING_FILES.with(|running| running.borrow_mut().push(file));
+}
+
+/// Stops running the innermost label.
+pub fn leave_label() {
+    RUNNING_FILES.with(|running| running.borrow_mut().pop());
+}
+
 pub fn print_error(msg: String) {
-    println!("{}", msg);
+    // Anything printed before the error is shown first
+    let _ = io::stdout().flush();
+
+    // Errors in the labels of an imported script say which script it is
+    match RUNNING_FILES.with(|running| running.borrow().last().cloned().flatten()) {
+        Some(file) => eprintln!("\nFile: {}{}", file, msg),
+        None => eprintln!("{}", msg),
+    }
     process::exit(1);
 }
@@ -0,0 +1,544 @@
+use crate::errors::Error;
+use crate::{ast, lexer};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Value(String),
+    Identifier(String),
+    Operator(String),
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    Value(String),
+    Variable(String),
+    Call(String, Vec<Expression>),
+    Unary(String, Box<Expression>),
+    Binary(String, Box<Expression>, Box<Expression>),
+}
+
+fn tokenize(tokens: &[String]) -> Result<Vec<Token>, Error> {
+    let mut result: Vec<Token> = Vec::new();
+
+    for token in tokens {
+        // Strings were already put together by the lexer
+        if crate::get_type(token.clone()) == ast::Types::String {
+            result.push(Token::Value(token.clone()));
+            continue;
+        }
+
+        let characters = token.chars().collect::<Vec<char>>();
+        let mut index = 0;
+
+        while index < characters.len() {
+            let c = characters[index];
+
+            if c.is_ascii_digit() {
+                let start = index;
+                while index < characters.len()
+                    && (characters[index].is_ascii_digit() || characters[index] == '.')
+                {
+                    index += 1;
+                }
+
+                let number = characters[start..index].iter().collect::<String>();
+                if crate::get_type(number.clone()) != ast::Types::Number {
+                    return Err(Error::RuntimeError(format!(
+                        "`{}` is not a valid number.",
+                        number
+                    )));
+                }
+
+                result.push(Token::Value(number));
+                continue;
+            }
+
+            if c.is_alphabetic() || c == '_' {
+                let start = index;
+                while index < characters.len() {
+                    let c = characters[index];
+                    let next = characters.get(index + 1);
+
+                    // A `.` joins the parts of a name such as `util.square`
+                    if c.is_alphanumeric()
+                        || c == '_'
+                        || (c == '.'
+                            && next.is_some_and(|next| next.is_alphabetic() || *next == '_'))
+                    {
+                        index += 1;
+                    } else {
+                        break;
+                    }
+                }
+
+                let word = characters[start..index].iter().collect::<String>();
+                result.push(match word.as_str() {
+                    "true" | "false" | "nil" => Token::Value(word),
+                    "and" => Token::Operator("&&".to_string()),
+                    "or" => Token::Operator("||".to_string()),
+                    "not" => Token::Operator("!".to_string()),
+                    _ => Token::Identifier(word),
+                });
+                continue;
+            }
+
+            let next = characters.get(index + 1).copied();
+
+            match (c, next) {
+                ('=', Some('='))
+                | ('!', Some('='))
+                | ('<', Some('='))
+                | ('>', Some('='))
+                | ('&', Some('&'))
+                | ('|', Some('|')) => {
+                    result.push(Token::Operator(format!("{}{}", c, next.unwrap())));
+                    index += 2;
+                    continue;
+                }
+
+                ('+', _)
+                | ('-', _)
+                | ('*', _)
+                | ('/', _)
+                | ('%', _)
+                | ('<', _)
+                | ('>', _)
+                | ('!', _) => result.push(Token::Operator(c.to_string())),
+
+                ('(', _) => result.push(Token::LeftParenthesis),
+
+                (')', _) => result.push(Token::RightParenthesis),
+
+                (',', _) => result.push(Token::Comma),
+
+                _ => {
+                    return Err(Error::RuntimeError(format!(
+                        "Unexpected character `{}` in expression.",
+                        c
+                    )))
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+// Operators from the loosest to the tightest binding
+const PRECEDENCE: [&[&str]; 5] = [
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["+", "-"],
+];
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expression, Error> {
+        if level == PRECEDENCE.len() {
+            return self.product();
+        }
+
+        let mut left = self.binary(level + 1)?;
+
+        while let Some(Token::Operator(operator)) = self.peek().cloned() {
+            if !PRECEDENCE[level].contains(&operator.as_str()) {
+                break;
+            }
+
+            self.next();
+            let right = self.binary(level + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expression, Error> {
+        let mut left = self.unary()?;
+
+        while let Some(Token::Operator(operator)) = self.peek().cloned() {
+            if !["*", "/", "%"].contains(&operator.as_str()) {
+                break;
+            }
+
+            self.next();
+            let right = self.unary()?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression, Error> {
+        if let Some(Token::Operator(operator)) = self.peek().cloned() {
+            if operator == "-" || operator == "!" {
+                self.next();
+                return Ok(Expression::Unary(operator, Box::new(self.unary()?)));
+            }
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expression, Error> {
+        match self.next() {
+            Some(Token::Value(value)) => Ok(Expression::Value(value)),
+
+            Some(Token::Identifier(name)) => {
+                if self.peek() != Some(&Token::LeftParenthesis) {
+                    return Ok(Expression::Variable(name));
+                }
+
+                self.next();
+                let mut arguments: Vec<Expression> = Vec::new();
+
+                if self.peek() == Some(&Token::RightParenthesis) {
+                    self.next();
+                    return Ok(Expression::Call(name, arguments));
+                }
+
+                loop {
+                    arguments.push(self.binary(0)?);
+
+                    match self.next() {
+                        Some(Token::Comma) => {}
+                        Some(Token::RightParenthesis) => break,
+                        _ => {
+                            return Err(Error::RuntimeError(format!(
+                                "Expected `,` or `)` in the arguments of `{}`.",
+                                name
+                            )))
+                        }
+                    }
+                }
+
+                Ok(Expression::Call(name, arguments))
+            }
+
+            Some(Token::LeftParenthesis) => {
+                let expression = self.binary(0)?;
+
+                match self.next() {
+                    Some(Token::RightParenthesis) => Ok(expression),
+                    _ => Err(Error::RuntimeError(
+                        "Expected `)` in expression.".to_string(),
+                    )),
+                }
+            }
+
+            Some(token) => Err(Error::RuntimeError(format!(
+                "Unexpected {} in expression.",
+                match token {
+                    Token::Operator(operator) => format!("`{}`", operator),
+                    Token::RightParenthesis => "`)`".to_string(),
+                    _ => "`,`".to_string(),
+                }
+            ))),
+
+            None => Err(Error::RuntimeError(
+                "Expression ended unexpectedly.".to_string(),
+            )),
+        }
+    }
+}
+
+fn to_number(value: &str) -> Result<f64, Error> {
+    if crate::get_type(value.to_string()) != ast::Types::Number {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is not a numeric type.",
+            value
+        )));
+    }
+
+    Ok(value.parse::<f64>().unwrap())
+}
+
+fn to_bool(value: &str) -> Result<bool, Error> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `Bool` is required.",
+            value,
+            crate::get_type(value.to_string())
+        ))),
+    }
+}
+
+fn evaluate_expression(
+    expression: &Expression,
+    line_number: usize,
+    string_line: &str,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> Result<String, Error> {
+    match expression {
+        Expression::Value(value) => Ok(value.clone()),
+
+        Expression::Variable(name) => Ok(crate::get_variable(name.clone(), variables)?.0),
+
+        Expression::Call(name, arguments) => {
+            // Calling a label runs it with the values as its arguments. In an imported script, its
+            // own labels are used without the namespace.
+            let label = [crate::scopes::namespace() + name, name.clone()]
+                .iter()
+                .find(|label| labels.contains_key(*label))
+                .cloned();
+
+            // Only labels and commands with a value can be called, which is checked before
+            // anything runs
+            if label.is_none() && !crate::produces_value(name) {
+                return Err(Error::RuntimeError(format!(
+                    "`{}` is neither a label nor a command that produces a value.",
+                    name
+                )));
+            }
+
+            let mut values: Vec<String> = Vec::new();
+            for argument in arguments {
+                values.push(evaluate_expression(
+                    argument,
+                    line_number,
+                    string_line,
+                    variables,
+                    labels,
+                )?);
+            }
+
+            match label {
+                Some(label) => {
+                    values.insert(0, label);
+                    crate::call_command(line_number, string_line, "call", values, variables, labels)
+                }
+                None => {
+                    crate::call_command(line_number, string_line, name, values, variables, labels)
+                }
+            }
+        }
+
+        Expression::Unary(operator, operand) => {
+            let value = evaluate_expression(operand, line_number, string_line, variables, labels)?;
+
+            if operator == "-" {
+                Ok(format!("{}", -to_number(&value)?))
+            } else {
+                Ok((!to_bool(&value)?).to_string())
+            }
+        }
+
+        Expression::Binary(operator, left, right) => {
+            let left = evaluate_expression(left, line_number, string_line, variables, labels)?;
+
+            // `&&` and `||` only evaluate the right side when they need to
+            if operator == "&&" || operator == "||" {
+                let left = to_bool(&left)?;
+                if left == (operator == "||") {
+                    return Ok(left.to_string());
+                }
+
+                let right =
+                    evaluate_expression(right, line_number, string_line, variables, labels)?;
+                return Ok(to_bool(&right)?.to_string());
+            }
+
+            let right = evaluate_expression(right, line_number, string_line, variables, labels)?;
+
+            match operator.as_str() {
+                "==" | "!=" | "<" | "<=" | ">" | ">=" => {
+                    let ordering = crate::compare_values(
+                        left,
+                        right,
+                        variables,
+                        operator != "==" && operator != "!=",
+                    )?;
+
+                    Ok(match operator.as_str() {
+                        "==" => ordering == Ordering::Equal,
+                        "!=" => ordering != Ordering::Equal,
+                        "<" => ordering == Ordering::Less,
+                        "<=" => ordering != Ordering::Greater,
+                        ">" => ordering == Ordering::Greater,
+                        _ => ordering != Ordering::Less,
+                    }
+                    .to_string())
+                }
+
+                "+" if crate::get_type(left.clone()) == ast::Types::String
+                    || crate::get_type(right.clone()) == ast::Types::String =>
+                {
+                    let mut result = String::new();
+
+                    for value in [&left, &right] {
+                        match crate::get_type(value.clone()) {
+                            ast::Types::String => {
+                                result += &crate::get_string_content(value.clone())
+                            }
+                            ast::Types::Number => result += value,
+                            _ => {
+                                return Err(Error::RuntimeError(format!(
+                                    "Cannot add as `{}` is neither a string nor a number.",
+                                    value
+                                )))
+                            }
+                        }
+                    }
+
+                    Ok("\"".to_owned() + &result + "\"")
+                }
+
+                _ => {
+                    let left = to_number(&left)?;
+                    let right = to_number(&right)?;
+
+                    if (operator == "/" || operator == "%") && right == 0.0 {
+                        return Err(Error::RuntimeError("Division by zero.".to_string()));
+                    }
+
+                    let result = match operator.as_str() {
+                        "+" => left + right,
+                        "-" => left - right,
+                        "*" => left * right,
+                        "/" => left / right,
+                        _ => left % right,
+                    };
+
+                    crate::math::check_finite(operator, result)?;
+
+                    Ok(format!("{}", result))
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates the expression made of `tokens` and returns the resulting value.
+pub fn evaluate(
+    tokens: &[String],
+    line_number: usize,
+    string_line: &str,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> Result<String, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(tokens)?,
+        position: 0,
+    };
+
+    if parser.tokens.is_empty() {
+        return Err(Error::RuntimeError("Expected an expression.".to_string()));
+    }
+
+    let expression = parser.binary(0)?;
+
+    if parser.position < parser.tokens.len() {
+        return Err(Error::RuntimeError(
+            "Unexpected tokens after the end of the expression.".to_string(),
+        ));
+    }
+
+    evaluate_expression(&expression, line_number, string_line, variables, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(code: &str) -> Result<String, Error> {
+        let tokens = code
+            .split_whitespace()
+            .map(|token| token.to_string())
+            .collect::<Vec<String>>();
+        let mut variables = HashMap::from([("x".to_string(), "4".to_string())]);
+
+        evaluate(&tokens, 1, code, &mut variables, &mut HashMap::new())
+    }
+
+    fn error(message: &str) -> Result<String, Error> {
+        Err(Error::RuntimeError(message.to_string()))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3"), Ok("7".to_string()));
+        assert_eq!(eval("(1 + 2) * 3"), Ok("9".to_string()));
+        assert_eq!(eval("10 - 4 - 3"), Ok("3".to_string()));
+        assert_eq!(eval("-2 * 3 + 10 % 4"), Ok("-4".to_string()));
+        assert_eq!(eval("x*x+1"), Ok("17".to_string()));
+        assert_eq!(eval("1 + 2 < 4 && !false"), Ok("true".to_string()));
+        assert_eq!(eval("1 < 2 == 3 < 2"), Ok("false".to_string()));
+        assert_eq!(eval("false or true and false"), Ok("false".to_string()));
+        assert_eq!(eval("\"a\" + 1 + 2"), Ok("\"a12\"".to_string()));
+    }
+
+    #[test]
+    fn dotted_names() {
+        assert_eq!(
+            tokenize(&["util.square(x)".to_string()]),
+            Ok(vec![
+                Token::Identifier("util.square".to_string()),
+                Token::LeftParenthesis,
+                Token::Identifier("x".to_string()),
+                Token::RightParenthesis,
+            ])
+        );
+    }
+
+    #[test]
+    fn short_circuit() {
+        assert_eq!(eval("true || 1 / 0 > 1"), Ok("true".to_string()));
+        assert_eq!(eval("false && missing"), Ok("false".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval(""), error("Expected an expression."));
+        assert_eq!(eval("1 +"), error("Expression ended unexpectedly."));
+        assert_eq!(eval("(1 + 2"), error("Expected `)` in expression."));
+        assert_eq!(
+            eval("1 2"),
+            error("Unexpected tokens after the end of the expression.")
+        );
+        assert_eq!(eval("* 2"), error("Unexpected `*` in expression."));
+        assert_eq!(
+            eval("1 $ 2"),
+            error("Unexpected character `$` in expression.")
+        );
+        assert_eq!(eval("1.2.3"), error("`1.2.3` is not a valid number."));
+        assert_eq!(eval("7 % 0"), error("Division by zero."));
+        assert_eq!(
+            eval("1 && true"),
+            error("`1` is of type `Number`, but `Bool` is required.")
+        );
+        assert_eq!(
+            eval("print(1)"),
+            error("`print` is neither a label nor a command that produces a value.")
+        );
+        assert_eq!(
+            eval("max(1 2)"),
+            error("Expected `,` or `)` in the arguments of `max`.")
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use crate::errors::Error;
+use crate::strings::MAX_LENGTH;
+use crate::{ast, math, output};
+use std::collections::HashMap;
+
+/// How a value is laid out, written after a `:` in a placeholder such as `{price:>8.2}`.
+///
+/// The parts, all optional and in this order, are a fill character followed by an alignment
+/// (`<`, `>` or `^`), a `0` to pad numbers with zeros, a width, a `.` followed by a precision and
+/// `x`, `X`, `o` or `b` to write a whole number in hexadecimal, octal or binary.
+struct Spec {
+    fill: char,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    radix: Option<char>,
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, Error> {
+    let invalid = || Error::RuntimeError(format!("`{}` is not a valid format specifier.", spec));
+    let characters = spec.chars().collect::<Vec<char>>();
+    let mut parsed = Spec {
+        fill: ' ',
+        align: None,
+        zero: false,
+        width: 0,
+        precision: None,
+        radix: None,
+    };
+    let mut index = 0;
+
+    let is_align = |c: Option<&char>| matches!(c, Some('<') | Some('>') | Some('^'));
+
+    if is_align(characters.get(1)) {
+        parsed.fill = characters[0];
+        parsed.align = Some(characters[1]);
+        index = 2;
+    } else if is_align(characters.first()) {
+        parsed.align = Some(characters[0]);
+        index = 1;
+    }
+
+    if characters.get(index) == Some(&'0') {
+        parsed.zero = true;
+        index += 1;
+    }
+
+    let digits = |index: &mut usize| {
+        let start = *index;
+        while characters.get(*index).is_some_and(|c| c.is_ascii_digit()) {
+            *index += 1;
+        }
+        characters[start..*index].iter().collect::<String>()
+    };
+
+    let width = digits(&mut index);
+    if !width.is_empty() {
+        parsed.width = width.parse::<usize>().map_err(|_| invalid())?;
+    }
+
+    if characters.get(index) == Some(&'.') {
+        index += 1;
+        let precision = digits(&mut index);
+        parsed.precision = Some(precision.parse::<usize>().map_err(|_| invalid())?);
+    }
+
+    if let Some(radix @ ('x' | 'X' | 'o' | 'b')) = characters.get(index) {
+        parsed.radix = Some(*radix);
+        index += 1;
+    }
+
+    if index != characters.len() {
+        return Err(invalid());
+    }
+
+    if parsed.width > MAX_LENGTH
+        || parsed
+            .precision
+            .is_some_and(|precision| precision > MAX_LENGTH)
+    {
+        return Err(Error::RuntimeError(format!(
+            "The width and precision of `{}` cannot be more than {}.",
+            spec, MAX_LENGTH
+        )));
+    }
+
+    Ok(parsed)
+}
+
+/// Writes `value` as text laid out by `spec`.
+///
+/// Numbers are aligned to the right and everything else to the left unless an alignment is
+/// given. The precision is the number of decimal places of a number, or the maximum number of
+/// characters of anything else.
+fn format_value(value: &str, spec: &Spec) -> Result<String, Error> {
+    let value_type = crate::get_type(value.to_string());
+
+    let mut text = to_text(value);
+
+    if let Some(radix) = spec.radix {
+        let number = match value_type {
+            ast::Types::Number => value.parse::<f64>().unwrap(),
+            _ => f64::NAN,
+        };
+
+        if number.fract() != 0.0 || number.abs() > math::MAX_SAFE_INTEGER {
+            return Err(Error::RuntimeError(format!(
+                "Cannot write `{}` with `{}` as it is not a whole number.",
+                value, radix
+            )));
+        }
+
+        let sign = if number < 0.0 { "-" } else { "" };
+        let magnitude = number.abs() as u64;
+
+        text = sign.to_owned()
+            + &match radix {
+                'x' => format!("{:x}", magnitude),
+                'X' => format!("{:X}", magnitude),
+                'o' => format!("{:o}", magnitude),
+                _ => format!("{:b}", magnitude),
+            };
+    } else if let Some(precision) = spec.precision {
+        if value_type == ast::Types::Number {
+            text = format!("{:.*}", precision, text.parse::<f64>().unwrap());
+        } else {
+            text = text.chars().take(precision).collect();
+        }
+    }
+
+    let padding = spec.width.saturating_sub(text.chars().count());
+
+    if padding == 0 {
+        return Ok(text);
+    }
+
+    // Zeros go between the sign and the digits of a number
+    if spec.zero && spec.align.is_none() && value_type == ast::Types::Number {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        return Ok(sign.to_owned() + &"0".repeat(padding) + digits);
+    }
+
+    let fill = if spec.zero && spec.align.is_none() {
+        '0'
+    } else {
+        spec.fill
+    };
+
+    let default_align = if value_type == ast::Types::Number {
+        '>'
+    } else {
+        '<'
+    };
+
+    let (left, right) = match spec.align.unwrap_or(default_align) {
+        '<' => (0, padding),
+        '>' => (padding, 0),
+        _ => (padding / 2, padding - padding / 2),
+    };
+
+    Ok(fill.to_string().repeat(left) + &text + &fill.to_string().repeat(right))
+}
+
+/// Returns `value` as it is printed, which is the content of a string and the value itself for
+/// anything else.
+pub fn to_text(value: &str) -> String {
+    match crate::get_type(value.to_string()) {
+        ast::Types::String => crate::get_string_content(value.to_string()),
+        _ => value.to_string(),
+    }
+}
+
+fn get_argument(arguments: &[String], position: usize, placeholder: &str) -> Result<String, Error> {
+    arguments.get(position).cloned().ok_or_else(|| {
+        let count = match arguments.len() {
+            1 => "is only 1 argument".to_string(),
+            count => format!("are only {} arguments", count),
+        };
+
+        Error::RuntimeError(format!(
+            "Placeholder `{{{}}}` has no argument as there {}.",
+            placeholder, count
+        ))
+    })
+}
+
+// Fills in the placeholders of `content`. `arguments` are the values for the `{}` and `{0}`
+// placeholders of a format string, which are left as they are when there are none.
+fn fill_placeholders(
+    content: &str,
+    arguments: Option<&[String]>,
+    variables: &HashMap<String, String>,
+) -> Result<String, Error> {
+    let characters = content.chars().collect::<Vec<char>>();
+    let mut result = String::new();
+    let mut index = 0;
+    let mut next_argument = 0;
+
+    while index < characters.len() {
+        let c = characters[index];
+        let next = characters.get(index + 1);
+
+        if (c == '{' && next == Some(&'{')) || (c == '}' && next == Some(&'}')) {
+            result.push(c);
+            index += 2;
+            continue;
+        }
+
+        // `${name}` in a regex replacement and `\p{Greek}` in a regex are not placeholders
+        let previous = index.checked_sub(1).map(|previous| characters[previous]);
+        let is_regex_syntax = previous == Some('$')
+            || (matches!(previous, Some('p') | Some('P'))
+                && index >= 2
+                && characters[index - 2] == '\\');
+
+        if c == '{' && !is_regex_syntax {
+            if let Some(length) = characters[index + 1..].iter().position(|c| *c == '}') {
+                let placeholder = characters[index + 1..index + 1 + length]
+                    .iter()
+                    .collect::<String>();
+                let (name, spec) = match placeholder.split_once(':') {
+                    Some((name, spec)) => (name.to_string(), spec),
+                    None => (placeholder.clone(), ""),
+                };
+
+                let value = if crate::get_type(name.clone()) == ast::Types::Identifier {
+                    if variables.contains_key(&name) {
+                        Some(crate::get_variable(name, variables)?.0)
+                    } else {
+                        None
+                    }
+                } else if let Some(arguments) = arguments {
+                    let position = if name.is_empty() {
+                        next_argument += 1;
+                        Some(next_argument - 1)
+                    } else {
+                        name.parse::<usize>().ok()
+                    };
+
+                    match position {
+                        Some(position) => Some(get_argument(arguments, position, &placeholder)?),
+                        None => None,
+                    }
+                } else {
+                    None
+                };
+
+                if let Some(value) = value {
+                    result.push_str(&format_value(&value, &parse_spec(spec)?)?);
+                    index += length + 2;
+                    continue;
+                }
+            }
+        }
+
+        result.push(c);
+        index += 1;
+    }
+
+    Ok(result)
+}
+
+/// Replaces every `{VARIABLE_NAME}` or `{VARIABLE_NAME:SPEC}` placeholder in the content of a
+/// string with the value of the variable.
+///
+/// `{{` and `}}` stand for `{` and `}`. Braces around anything that is not the name of a
+/// variable, as in the regex `[0-9]{3}`, are left as they are, and so are the `${name}` of a regex
+/// replacement and the `\p{Greek}` of a regex.
+pub fn interpolate(content: &str, variables: &HashMap<String, String>) -> Result<String, Error> {
+    fill_placeholders(content, None, variables)
+}
+
+/// Doubles the braces of a string so that `interpolate` gives it back unchanged.
+pub fn escape(string: &str) -> String {
+    string.replace('{', "{{").replace('}', "}}")
+}
+
+/// Whether argument `index` of `command` is a format string, which the command fills in itself
+/// instead of it being interpolated beforehand.
+pub fn is_format_string(command: &str, index: usize) -> bool {
+    index == 0 && (command == "printf" || command == "format")
+}
+
+/// Runs `printf`, `format` or `println`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    if command == "println" {
+        if args.len() > 1 {
+            return Err(Error::RuntimeError(format!(
+                "Expected 0 or 1 arguments, got {}.",
+                args.len()
+            )));
+        }
+
+        let text = match args.first() {
+            Some(arg) => to_text(&crate::resolve_value(arg.clone(), variables)?.0),
+            None => String::new(),
+        };
+
+        return output::write(&(text + "\n"));
+    }
+
+    if args.is_empty() {
+        return Err(Error::RuntimeError(
+            "Expected at least 1 argument, got 0.".to_string(),
+        ));
+    }
+
+    let (template, template_type) = crate::resolve_value(args[0].clone(), variables)?;
+
+    if template_type != ast::Types::String {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `String` is required.",
+            args[0], template_type
+        )));
+    }
+
+    let arguments = args[1..]
+        .iter()
+        .map(|arg| Ok(crate::resolve_value(arg.clone(), variables)?.0))
+        .collect::<Result<Vec<String>, Error>>()?;
+
+    let result = fill_placeholders(
+        &crate::get_string_content(template),
+        Some(&arguments),
+        variables,
+    )?;
+
+    if command == "printf" {
+        output::write(&result)?;
+    } else {
+        variables.insert("TEMP".to_string(), "\"".to_owned() + &result + "\"");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(value: &str, spec: &str) -> Result<String, Error> {
+        format_value(value, &parse_spec(spec)?)
+    }
+
+    fn ok(text: &str) -> Result<String, Error> {
+        Ok(text.to_string())
+    }
+
+    #[test]
+    fn zero_padding() {
+        assert_eq!(format("-42", "06"), ok("-00042"));
+        assert_eq!(format("42", "06"), ok("000042"));
+        assert_eq!(format("-1.5", "08.2"), ok("-0001.50"));
+        // An alignment pads with the fill character instead
+        assert_eq!(format("-42", ">06"), ok("   -42"));
+    }
+
+    #[test]
+    fn radix() {
+        assert_eq!(format("255", "x"), ok("ff"));
+        assert_eq!(format("255", "X"), ok("FF"));
+        assert_eq!(format("8", "o"), ok("10"));
+        assert_eq!(format("5", "08b"), ok("00000101"));
+        assert_eq!(format("-255", "x"), ok("-ff"));
+        assert_eq!(
+            format("2.5", "x"),
+            Err(Error::RuntimeError(
+                "Cannot write `2.5` with `x` as it is not a whole number.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(format("3.14159", ".2"), ok("3.14"));
+        assert_eq!(format("2", ".3"), ok("2.000"));
+        assert_eq!(format("3.14159", "8.2"), ok("    3.14"));
+        assert_eq!(format("\"hello\"", ".3"), ok("hel"));
+    }
+
+    #[test]
+    fn alignment() {
+        assert_eq!(format("5", "4"), ok("   5"));
+        assert_eq!(format("\"ab\"", "4"), ok("ab  "));
+        assert_eq!(format("\"ab\"", "*^7"), ok("**ab***"));
+        assert_eq!(format("\"abcdef\"", ">3"), ok("abcdef"));
+    }
+
+    #[test]
+    fn invalid_specs() {
+        assert_eq!(
+            format("1", "99999999999999"),
+            Err(Error::RuntimeError(
+                "The width and precision of `99999999999999` cannot be more than 1073741824."
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            format("1", ".1073741825"),
+            Err(Error::RuntimeError(
+                "The width and precision of `.1073741825` cannot be more than 1073741824."
+                    .to_string()
+            ))
+        );
+
+        for spec in ["5z", ".", "x5", "^^^"] {
+            assert_eq!(
+                format("1", spec),
+                Err(Error::RuntimeError(format!(
+                    "`{}` is not a valid format specifier.",
+                    spec
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn placeholders() {
+        let variables = HashMap::from([
+            ("name".to_string(), "\"Ann\"".to_string()),
+            ("price".to_string(), "4.5".to_string()),
+        ]);
+
+        assert_eq!(
+            interpolate("{name} pays {price:.2} {{in}} {missing}", &variables),
+            ok("Ann pays 4.50 {in} {missing}")
+        );
+        assert_eq!(
+            interpolate("[0-9]{3} ${name} \\p{L} {name}", &variables),
+            ok("[0-9]{3} ${name} \\p{L} Ann")
+        );
+
+        let arguments = ["1".to_string(), "\"b\"".to_string()];
+        assert_eq!(
+            fill_placeholders("{} {} {0:>3}", Some(&arguments), &variables),
+            ok("1 b   1")
+        );
+        assert_eq!(
+            fill_placeholders("{2}", Some(&arguments), &variables),
+            Err(Error::RuntimeError(
+                "Placeholder `{2}` has no argument as there are only 2 arguments.".to_string()
+            ))
+        );
+    }
+}
@@ -1,65 +1,428 @@
 use crate::errors::Error;
+use std::str::Chars;
 
 #[derive(Debug, Clone)]
 pub struct Line(pub Vec<String>);
 
+// Reads the escape sequence following a `\` in a string and returns the text it stands for. A `\`
+// followed by anything else is kept as it is, so that regexes such as `\d+` can be written
+fn read_escape(characters: &mut Chars) -> Result<String, String> {
+    let escaped = match characters.next() {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('0') => '\0',
+        Some('"') => '"',
+        Some('\\') => '\\',
+
+        Some('u') => {
+            if characters.next() != Some('{') {
+                return Err("Expected `{` after `\\u`".to_string());
+            }
+
+            let mut hex = String::new();
+            loop {
+                match characters.next() {
+                    Some('}') => break,
+                    Some(c) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(c),
+                    _ => {
+                        return Err(
+                            "Expected 1 to 6 hexadecimal digits and a `}` after `\\u{`".to_string()
+                        )
+                    }
+                }
+            }
+
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(std::char::from_u32)
+                .ok_or(format!("`\\u{{{}}}` is not a valid character", hex))?
+        }
+
+        Some(c) => return Ok(format!("\\{}", c)),
+
+        None => return Err("Expected an escape sequence after `\\`".to_string()),
+    };
+
+    Ok(escaped.to_string())
+}
+
+// Returns the terminator of a heredoc token such as `<<END` and whether it is a `<<-END` heredoc,
+// which strips the indentation shared by its lines
+fn heredoc_terminator(token: &str) -> Option<(String, bool)> {
+    let rest = token.strip_prefix("<<")?;
+    let (terminator, strip_indentation) = match rest.strip_prefix('-') {
+        Some(terminator) => (terminator, true),
+        None => (rest, false),
+    };
+
+    if !terminator.is_empty() && terminator.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Some((terminator.to_string(), strip_indentation))
+    } else {
+        None
+    }
+}
+
+// Returns the content of a heredoc starting at the first of `lines`, along with the number of
+// lines it took including its terminator
+fn read_heredoc(
+    lines: &[&str],
+    terminator: &str,
+    strip_indentation: bool,
+) -> Option<(String, usize)> {
+    let end = lines.iter().position(|line| {
+        if strip_indentation {
+            line.trim() == terminator
+        } else {
+            *line == terminator
+        }
+    })?;
+
+    let mut content = lines[..end].to_vec();
+
+    if strip_indentation {
+        let indentation = content
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start_matches(&[' ', '\t'][..]).len())
+            .min()
+            .unwrap_or(0);
+
+        content = content
+            .iter()
+            .map(|line| line.get(indentation..).unwrap_or(""))
+            .collect();
+    }
+
+    Some((content.join("\n"), end + 1))
+}
+
 pub fn lex(code: String) -> (Vec<Line>, Error) {
     let mut lexed_code: Vec<Line> = Vec::new();
     let mut lexed_code_line: Vec<String> = Vec::new();
     let mut temp: String = String::new(); // Will be used to add to lexed_code_line
     let mut is_string: bool = false;
+    let mut is_raw: bool = false; // Whether the string is a raw string such as `r"C:\temp"`
     let mut temp_string: String = String::new(); // Will be used for strings in ll
-    let mut error: Error = Error::None;
-
-    for (line_number, line) in code.replace('\r', "").replace('\t', "").trim().split('\n').enumerate() {
-        let line_characters = &(*line).chars().collect::<Vec<char>>();
-        for (character_index, &c) in line_characters.clone().iter().enumerate() {
-            if c == '"' {
-                if !is_string {
-                    is_string = true
-                } else {
-                    is_string = false;
-                    temp_string.push(c);
+    let mut block_comment_start: Option<usize> = None; // The line a `/*` comment started on
+    let mut continued_lines = 0; // How many lines the current line was continued onto with `\`
+
+    let code = code.replace('\r', "");
+    let lines = code.trim_end().split('\n').collect::<Vec<&str>>();
+    let mut line_index = 0;
+
+    while line_index < lines.len() {
+        let line = lines[line_index];
+        let line_number = line_index + 1;
+        line_index += 1;
+        let mut characters = line.chars();
+        let mut column = 0;
+        let mut is_continued = false;
+
+        // A string continued from the line before goes on after the indentation of this one
+        if is_string {
+            while characters.as_str().starts_with([' ', '\t']) {
+                characters.next();
+                column += 1;
+            }
+        }
+
+        // The rest of a `comment` line is not lexed, so it can contain anything
+        if block_comment_start.is_none()
+            && continued_lines == 0
+            && line.split_whitespace().next() == Some("comment")
+        {
+            lexed_code.push(Line(vec!["comment".to_string()]));
+            continue;
+        }
+
+        while let Some(c) = characters.next() {
+            column += 1;
+
+            if block_comment_start.is_some() {
+                if c == '*' && characters.as_str().starts_with('/') {
+                    characters.next();
+                    column += 1;
+                    block_comment_start = None;
                 }
+
+                continue;
             }
 
-            if is_string && character_index == line_characters.len() - 1 {
-                error = Error::LexingError(format!(
-                    "\nCode:\n{} | {}\nProblem: String was never ended",
-                    line_number,
-                    &(*line)
-                ));
-                break;
-            } else if is_string {
-                temp_string.push(c);
-            } else {
-                if !temp_string.is_empty() {
-                    lexed_code_line.push(temp_string.clone());
-                    temp_string = String::new();
+            if is_string {
+                match c {
+                    '"' => {
+                        temp_string.push(c);
+                        lexed_code_line.push(temp_string.clone());
+                        temp_string = String::new();
+                        is_string = false;
+                        is_raw = false;
+                    }
+
+                    // Raw strings keep every character as it is
+                    _ if is_raw => temp_string.push(c),
+
+                    '\\' if characters.as_str().trim().is_empty() => {
+                        is_continued = true;
+                        break;
+                    }
+
+                    '\\' => match read_escape(&mut characters) {
+                        Ok(escaped) => temp_string.push_str(&escaped),
+                        Err(problem) => {
+                            return (
+                                lexed_code,
+                                Error::LexingError(format!(
+                                    "\nCode:\n{} | {}\nProblem: {} at column {}.",
+                                    line_number, line, problem, column
+                                )),
+                            );
+                        }
+                    },
+
+                    _ => temp_string.push(c),
+                }
+
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    // An `r` right before the quote starts a raw string
+                    if temp == "r" {
+                        is_raw = true;
+                        temp = String::new();
+                    }
+
+                    // Keeps tokens in order when a string follows something like `len(`
+                    if !temp.is_empty() {
+                        lexed_code_line.push(temp.clone());
+                        temp = String::new();
+                    }
+
+                    is_string = true;
+                    temp_string.push(c);
                 }
 
-                if c == ' ' {
+                ' ' | '\t' => {
                     lexed_code_line.push(temp.clone());
                     temp = String::new();
-                } else if character_index == line_characters.len() - 1 && c != '"' {
-                    temp.push(c);
+                }
+
+                // The rest of the line is a comment
+                '#' | ';' => break,
+
+                '\\' if characters.as_str().trim().is_empty() => {
+                    is_continued = true;
+                    break;
+                }
+
+                '/' if characters.as_str().starts_with('*') => {
                     lexed_code_line.push(temp.clone());
                     temp = String::new();
-                } else if !vec!['\"'].contains(&c) {
-                    temp.push(c);
+
+                    characters.next();
+                    column += 1;
+                    block_comment_start = Some(line_number);
                 }
+
+                _ => temp.push(c),
             }
         }
 
+        if is_continued {
+            lexed_code_line.push(temp.clone());
+            temp = String::new();
+
+            if line_index == lines.len() {
+                return (
+                    lexed_code,
+                    Error::LexingError(format!(
+                        "\nCode:\n{} | {}\nProblem: The last line cannot be continued with `\\`",
+                        line_number, line
+                    )),
+                );
+            }
+
+            continued_lines += 1;
+            continue;
+        }
+
+        if is_string {
+            return (
+                lexed_code,
+                Error::LexingError(format!(
+                    "\nCode:\n{} | {}\nProblem: String was never ended",
+                    line_number, line
+                )),
+            );
+        }
+
+        lexed_code_line.push(temp.clone());
+        temp = String::new();
+
         lexed_code_line.retain(|x| !(*x).is_empty());
 
+        // A heredoc takes the lines after it until its terminator and becomes a string
+        let mut consumed_lines = 0;
+        if let Some(last) = lexed_code_line.last_mut() {
+            if let Some((terminator, strip_indentation)) = heredoc_terminator(last) {
+                match read_heredoc(&lines[line_index..], &terminator, strip_indentation) {
+                    Some((content, line_count)) => {
+                        *last = "\"".to_owned() + &content + "\"";
+                        consumed_lines = line_count;
+                    }
+                    None => {
+                        return (
+                            lexed_code,
+                            Error::LexingError(format!(
+                                "\nCode:\n{} | {}\nProblem: Heredoc was never ended with `{}`",
+                                line_number, line, terminator
+                            )),
+                        );
+                    }
+                }
+            }
+        }
+
         lexed_code.push(Line(lexed_code_line));
         lexed_code_line = Vec::new();
+
+        // A line continued with `\` belongs to the line it started on, and the lines it was
+        // continued onto are left empty along with those taken by a heredoc so that line numbers
+        // stay the same
+        for _ in 0..continued_lines + consumed_lines {
+            lexed_code.push(Line(Vec::new()));
+        }
+        line_index += consumed_lines;
+        continued_lines = 0;
+    }
+
+    if let Some(start) = block_comment_start {
+        return (
+            lexed_code,
+            Error::LexingError(format!(
+                "\nCode:\n{} | {}\nProblem: Block comment was never ended",
+                start,
+                lines[start - 1]
+            )),
+        );
     }
 
     if lexed_code.is_empty() {
-        error = Error::LexingError("No code found".to_string());
+        return (lexed_code, Error::LexingError("No code found".to_string()));
+    }
+
+    (lexed_code, Error::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex_lines(code: &str) -> Vec<Vec<String>> {
+        let (lines, error) = lex(code.to_string());
+        assert_eq!(error, Error::None);
+
+        lines.into_iter().map(|Line(tokens)| tokens).collect()
+    }
+
+    fn lex_error(code: &str) -> String {
+        match lex(code.to_string()).1 {
+            Error::LexingError(message) => message,
+            error => panic!("Expected a lexing error, got {:?}", error),
+        }
     }
 
-    (lexed_code, error)
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            lex_lines(r#"print "a\tb\u{e9}\"\\""#),
+            vec![vec!["print".to_string(), "\"a\tbé\"\\\"".to_string()]]
+        );
+    }
+
+    #[test]
+    fn unknown_escapes_and_raw_strings() {
+        assert_eq!(
+            lex_lines(r#"is_match s "\d+\.\w" "#),
+            vec![vec![
+                "is_match".to_string(),
+                "s".to_string(),
+                r#""\d+\.\w""#.to_string()
+            ]]
+        );
+        assert_eq!(
+            lex_lines(r#"var path r"C:\temp\new" "#),
+            vec![vec![
+                "var".to_string(),
+                "path".to_string(),
+                r#""C:\temp\new""#.to_string()
+            ]]
+        );
+    }
+
+    #[test]
+    fn escape_errors() {
+        assert_eq!(
+            lex_error("label .ENTRY\n\tprint \"\\u{110000}\""),
+            "\nCode:\n2 | \tprint \"\\u{110000}\"\nProblem: `\\u{110000}` is not a valid character at column 9."
+        );
+        assert_eq!(
+            lex_error(r#"print "\u{zz}""#),
+            "\nCode:\n1 | print \"\\u{zz}\"\nProblem: Expected 1 to 6 hexadecimal digits and a `}` after `\\u{` at column 8."
+        );
+    }
+
+    #[test]
+    fn heredoc_strips_shared_indentation() {
+        let code = "label .ENTRY\n\tvar query <<-SQL\n\t\tSELECT name\n\t\t  FROM users\n\n\tSQL\n\tprint query";
+
+        assert_eq!(
+            lex_lines(code),
+            vec![
+                vec!["label".to_string(), ".ENTRY".to_string()],
+                vec![
+                    "var".to_string(),
+                    "query".to_string(),
+                    "\"SELECT name\n  FROM users\n\"".to_string()
+                ],
+                // The lines of the heredoc are left empty so that line numbers stay the same
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec!["print".to_string(), "query".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn heredoc_keeps_lines_verbatim() {
+        assert_eq!(
+            lex_lines("var text <<END\n  indented\nEND\nprint text"),
+            vec![
+                vec![
+                    "var".to_string(),
+                    "text".to_string(),
+                    "\"  indented\"".to_string()
+                ],
+                vec![],
+                vec![],
+                vec!["print".to_string(), "text".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn heredoc_errors() {
+        assert_eq!(
+            lex_error("var text <<END\nsome text\n  END"),
+            "\nCode:\n1 | var text <<END\nProblem: Heredoc was never ended with `END`"
+        );
+        assert_eq!(
+            lex_error("var text <<END\nEND\nprint \"a"),
+            "\nCode:\n3 | print \"a\nProblem: String was never ended"
+        );
+    }
 }
@@ -1,27 +1,45 @@
 // #![allow(warnings, unused)]
-use regex::Regex;
 use std::collections::HashMap;
 use std::env::{self, args};
 use std::fs::File;
 use std::fs::{self};
 use std::io::Read;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 mod ast;
+mod collections;
+mod constants;
 mod errors;
+mod expression;
+mod format;
 mod lexer;
+mod math;
+mod output;
+mod random;
+mod regexes;
+mod scopes;
+mod strings;
 
 use errors::*;
 
 fn get_type(token: String) -> ast::Types {
-    let number_re = Regex::new(r"^[0-9]+$").unwrap();
-    let identifier_re = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
+    let number_re = regexes::compile(r"^-?[0-9]+(\.[0-9]+)?$").unwrap();
+    let identifier_re = regexes::compile(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
 
     if token.starts_with('\"') && token.ends_with('\"') {
         ast::Types::String
     } else if number_re.is_match(&token) {
         ast::Types::Number
+    } else if token == "true" || token == "false" {
+        ast::Types::Bool
+    } else if token == "nil" {
+        ast::Types::Nil
+    } else if token.starts_with('[') && token.ends_with(']') {
+        ast::Types::List
+    } else if token.starts_with('{') && token.ends_with('}') {
+        ast::Types::Map
     } else if identifier_re.is_match(&token) {
         ast::Types::Identifier
     } else {
@@ -35,6 +53,14 @@ fn string_to_type(string: String) -> Result<ast::Types, Error> {
 
         "String" => Ok(ast::Types::String),
 
+        "Bool" => Ok(ast::Types::Bool),
+
+        "List" => Ok(ast::Types::List),
+
+        "Map" => Ok(ast::Types::Map),
+
+        "Nil" => Ok(ast::Types::Nil),
+
         _ => Err(Error::RuntimeError("Invalid type".to_string())),
     }
 }
@@ -47,451 +73,859 @@ fn rem_first_and_last(value: &str) -> &str {
 }
 
 fn get_string_content(string: String) -> String {
-    rem_first_and_last(&string).to_string().replace("\\n", "\n")
+    rem_first_and_last(&string).to_string()
+}
+
+// An alias made with `alias` is stored as this followed by the name of the variable it refers to.
+// Since tokens cannot contain spaces, no other value looks like it.
+const ALIAS_PREFIX: &str = "alias ";
+
+fn alias_target(value: &str) -> Option<&str> {
+    value.strip_prefix(ALIAS_PREFIX)
 }
 
+/// Returns the value of a variable as it was stored, following aliases to the variable they
+/// refer to.
 fn get_variable(
     variable_name: String,
-    variables: HashMap<String, String>,
+    variables: &HashMap<String, String>,
 ) -> Result<(String, ast::Types), Error> {
-    match variables.get(&variable_name) {
-        Some(value) => {
-            let value = (*value).to_string();
-            let value_type = get_type(value.clone());
-            if value_type == ast::Types::Identifier {
-                let value = get_variable(value, variables)?;
-                Ok((value.0, value.1))
-            } else {
-                Ok((value, value_type))
+    let mut name = variable_name.clone();
+    let mut visited: Vec<String> = Vec::new();
+
+    loop {
+        match variables.get(&name) {
+            Some(value) => match alias_target(value) {
+                Some(target) => {
+                    visited.push(name);
+                    name = target.to_string();
+
+                    if visited.contains(&name) {
+                        return Err(Error::RuntimeError(format!(
+                            "Variable `{}` is an alias that refers back to itself.",
+                            variable_name
+                        )));
+                    }
+                }
+
+                None => return Ok((value.clone(), get_type(value.clone()))),
+            },
+
+            None => {
+                return Err(Error::RuntimeError(format!(
+                    "Variable `{}` does not exist.",
+                    name
+                )))
             }
         }
+    }
+}
 
-        None => Err(Error::RuntimeError(format!(
-            "Variable `{}` does not exist.",
-            variable_name
-        ))),
+fn resolve_value(
+    value: String,
+    variables: &HashMap<String, String>,
+) -> Result<(String, ast::Types), Error> {
+    let value_type = get_type(value.clone());
+
+    if value_type == ast::Types::Identifier {
+        get_variable(value, variables)
+    } else {
+        Ok((value, value_type))
     }
 }
 
-fn interpret(
-    lexed_code: Vec<(usize, lexer::Line)>,
-    variables: &mut HashMap<String, String>,
-    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
-) {
-    for (line_number, line) in lexed_code.iter() {
-        let line: Vec<String> = line.clone().0;
-        let string_line = line.clone().join(" ");
+fn expect_args(args: &[String], expected: usize) -> Result<(), Error> {
+    if args.len() != expected {
+        Err(Error::RuntimeError(format!(
+            "Expected {} argument{}, got {}.",
+            expected,
+            if expected == 1 { "" } else { "s" },
+            args.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
 
-        let command: String = line[0].clone();
-        let args: Vec<String> = line[1..].to_vec().clone();
-        let args_len = args.len();
+fn resolve_map(
+    map: String,
+    variables: &HashMap<String, String>,
+) -> Result<Vec<(String, String)>, Error> {
+    let (value, value_type) = resolve_value(map.clone(), variables)?;
+
+    if value_type != ast::Types::Map {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `Map` is required.",
+            map, value_type
+        )));
+    }
 
-        match command.clone().as_str() {
-            "exists" => {
-                if args_len != 2 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let variable_name = args[0].clone();
+    collections::parse_map(&value)
+}
 
-                    if !variables.contains_key(&variable_name) {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Variable `{}` does not exist.",
-                            line_number,
-                            string_line.clone(),
-                            variable_name
-                        ));
-                    }
+fn resolve_key(key: String, variables: &HashMap<String, String>) -> Result<String, Error> {
+    let (value, value_type) = resolve_value(key.clone(), variables)?;
 
-                    let variable_type = get_type(variables.get(&variable_name).unwrap().clone());
-                    let variable_required_type = string_to_type(args[1].clone());
+    if value_type != ast::Types::String && value_type != ast::Types::Number {
+        return Err(Error::RuntimeError(format!(
+            "Key `{}` is neither a string nor a number.",
+            key
+        )));
+    }
 
-                    match variable_required_type.clone() {
-                        Ok(required_type) => {
-                            if variable_type != required_type {
-                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `{}` is required.", line_number, string_line.clone(), variable_name, variable_type, required_type));
-                            }
-                        }
-                        Err(e) => {
-                            print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: {}",
-                                line_number,
-                                string_line.clone(),
-                                e
-                            ));
-                        }
-                    }
-                }
-            }
+    Ok(value)
+}
 
-            "var" => {
-                if args_len != 2 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let variable_name = args[0].clone();
-                    let mut variable_value = args[1].clone();
+fn map_command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    match command {
+        "map_new" => {
+            expect_args(args, 0)?;
+            variables.insert("TEMP".to_string(), collections::make_map(&[]));
+        }
 
-                    if get_type(variable_value.clone()) == ast::Types::Identifier {
-                        match get_variable(variable_value.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                variable_value = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+        "map_set" => {
+            expect_args(args, 3)?;
+            let mut entries = resolve_map(args[0].clone(), variables)?;
+            let key = resolve_key(args[1].clone(), variables)?;
+            let (value, _) = resolve_value(args[2].clone(), variables)?;
+
+            match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key, value)),
+            }
+
+            variables.insert(args[0].clone(), collections::make_map(&entries));
+        }
 
-                    variables.insert(variable_name.clone(), variable_value.clone());
+        "map_get" => {
+            if args.len() != 2 && args.len() != 3 {
+                return Err(Error::RuntimeError(format!(
+                    "Expected 2 or 3 arguments, got {}.",
+                    args.len()
+                )));
+            }
+            let entries = resolve_map(args[0].clone(), variables)?;
+            let key = resolve_key(args[1].clone(), variables)?;
+
+            let value = match entries.into_iter().find(|(k, _)| *k == key) {
+                Some((_, value)) => value,
+                None if args.len() == 3 => resolve_value(args[2].clone(), variables)?.0,
+                None => {
+                    return Err(Error::RuntimeError(format!(
+                        "Map `{}` has no key {}.",
+                        args[0], key
+                    )))
                 }
+            };
+
+            variables.insert("TEMP".to_string(), value);
+        }
+
+        "map_has" => {
+            expect_args(args, 2)?;
+            let entries = resolve_map(args[0].clone(), variables)?;
+            let key = resolve_key(args[1].clone(), variables)?;
+
+            variables.insert(
+                "TEMP".to_string(),
+                entries.iter().any(|(k, _)| *k == key).to_string(),
+            );
+        }
+
+        "map_remove" => {
+            expect_args(args, 2)?;
+            let mut entries = resolve_map(args[0].clone(), variables)?;
+            let key = resolve_key(args[1].clone(), variables)?;
+
+            entries.retain(|(k, _)| *k != key);
+
+            variables.insert(args[0].clone(), collections::make_map(&entries));
+        }
+
+        "map_keys" => {
+            expect_args(args, 1)?;
+            let entries = resolve_map(args[0].clone(), variables)?;
+            let keys: Vec<String> = entries.into_iter().map(|(k, _)| k).collect();
+
+            variables.insert("TEMP".to_string(), collections::make_list(&keys));
+        }
+
+        "map_values" => {
+            expect_args(args, 1)?;
+            let entries = resolve_map(args[0].clone(), variables)?;
+            let values: Vec<String> = entries.into_iter().map(|(_, v)| v).collect();
+
+            variables.insert("TEMP".to_string(), collections::make_list(&values));
+        }
+
+        "map_len" => {
+            expect_args(args, 1)?;
+            let entries = resolve_map(args[0].clone(), variables)?;
+
+            variables.insert("TEMP".to_string(), entries.len().to_string());
+        }
+
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+fn resolve_bool(value: String, variables: &HashMap<String, String>) -> Result<bool, Error> {
+    let (resolved, resolved_type) = resolve_value(value.clone(), variables)?;
+
+    if resolved_type != ast::Types::Bool {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `Bool` is required.",
+            value, resolved_type
+        )));
+    }
+
+    Ok(resolved == "true")
+}
+
+fn compare_values(
+    item1: String,
+    item2: String,
+    variables: &HashMap<String, String>,
+    ordering_only: bool,
+) -> Result<std::cmp::Ordering, Error> {
+    let (value1, type1) = resolve_value(item1.clone(), variables)?;
+    let (value2, type2) = resolve_value(item2.clone(), variables)?;
+
+    if type1 != type2 {
+        return Err(Error::RuntimeError(format!(
+            "Cannot compare `{}` and `{}` as they are not the same type.",
+            item1, item2
+        )));
+    }
+
+    match type1 {
+        ast::Types::Number => {
+            let number1 = value1.parse::<f64>().unwrap();
+            let number2 = value2.parse::<f64>().unwrap();
+
+            number1.partial_cmp(&number2).ok_or_else(|| {
+                Error::RuntimeError(format!("Cannot compare `{}` and `{}`.", item1, item2))
+            })
+        }
+
+        // Strings are ordered character by character.
+        ast::Types::String => Ok(get_string_content(value1).cmp(&get_string_content(value2))),
+
+        _ if ordering_only => Err(Error::RuntimeError(format!(
+            "Cannot compare `{}` and `{}` as `{}` is neither a number nor a string.",
+            item1, item2, item1
+        ))),
+
+        _ => Ok(value1.cmp(&value2)),
+    }
+}
+
+fn logic_command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    let result = match command {
+        "eq" | "ne" => {
+            expect_args(args, 2)?;
+            let ordering = compare_values(args[0].clone(), args[1].clone(), variables, false)?;
+
+            (ordering == std::cmp::Ordering::Equal) == (command == "eq")
+        }
+
+        "lt" | "le" | "gt" | "ge" => {
+            expect_args(args, 2)?;
+            let ordering = compare_values(args[0].clone(), args[1].clone(), variables, true)?;
+
+            match command {
+                "lt" => ordering == std::cmp::Ordering::Less,
+                "le" => ordering != std::cmp::Ordering::Greater,
+                "gt" => ordering == std::cmp::Ordering::Greater,
+                _ => ordering != std::cmp::Ordering::Less,
             }
+        }
 
-            "print" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut to_print = args[0].clone();
+        "and" | "or" => {
+            expect_args(args, 2)?;
+            let bool1 = resolve_bool(args[0].clone(), variables)?;
+            let bool2 = resolve_bool(args[1].clone(), variables)?;
 
-                    match get_type(to_print.clone()) {
-                        ast::Types::Number => {}
+            if command == "and" {
+                bool1 && bool2
+            } else {
+                bool1 || bool2
+            }
+        }
 
-                        ast::Types::String => {
-                            to_print = get_string_content(to_print.clone());
-                        }
+        "not" => {
+            expect_args(args, 1)?;
+            !resolve_bool(args[0].clone(), variables)?
+        }
 
-                        // Variables
-                        ast::Types::Identifier => {
-                            match get_variable(to_print.clone(), variables.clone()) {
-                                Ok((value, _)) => {
-                                    to_print = value;
-                                }
-                                Err(e) => {
-                                    print_error(format!(
-                                        "\nCode:\n{} | {}\nProblem: {}",
-                                        line_number,
-                                        string_line.clone(),
-                                        e
-                                    ));
-                                }
-                            }
+        _ => unreachable!(),
+    };
 
-                            let to_print_type = get_type(to_print.clone());
+    variables.insert("TEMP".to_string(), result.to_string());
 
-                            if to_print_type == ast::Types::String {
-                                to_print = get_string_content(to_print.clone());
-                            }
-                        }
+    Ok(())
+}
 
-                        _ => {
-                            print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Invalid type.",
-                                line_number,
-                                string_line.clone()
-                            ));
-                        }
-                    }
+fn variable_command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    expect_args(args, 1)?;
+
+    if command != "typeof" && get_type(args[0].clone()) != ast::Types::Identifier {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is not a valid variable name.",
+            args[0]
+        )));
+    }
 
-                    print!("{}", to_print);
+    match command {
+        // Unsetting a variable that does not exist does nothing
+        "unset" => {
+            variables.remove(&args[0]);
+        }
+
+        "is_defined" => {
+            let is_defined = get_variable(args[0].clone(), variables).is_ok();
+            variables.insert("TEMP".to_string(), is_defined.to_string());
+        }
+
+        "typeof" => {
+            let (value, value_type) = resolve_value(args[0].clone(), variables)?;
+
+            match value_type {
+                ast::Types::String
+                | ast::Types::Number
+                | ast::Types::Bool
+                | ast::Types::Nil
+                | ast::Types::List
+                | ast::Types::Map => {
+                    variables.insert("TEMP".to_string(), format!("\"{}\"", value_type));
                 }
-            }
 
-            "print_newline" => {
-                if args_len != 0 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    println!();
+                _ => {
+                    return Err(Error::RuntimeError(format!(
+                        "`{}` does not hold a value of a known type.",
+                        value
+                    )))
                 }
             }
+        }
 
-            "add" => {
-                if args_len != 2 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
+        _ => unreachable!(),
+    }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+    Ok(())
+}
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+fn foreach_items(
+    collection: String,
+    variables: &HashMap<String, String>,
+) -> Result<Vec<String>, Error> {
+    let (value, value_type) = resolve_value(collection.clone(), variables)?;
 
-                    match (get_type(item1.clone()), get_type(item2.clone())) {
-                        (ast::Types::String, ast::Types::String) => {
-                            variables.insert(
-                                "TEMP".to_string(),
-                                "\"".to_owned()
-                                    + &get_string_content(item1.clone())
-                                    + &get_string_content(item2.clone())
-                                    + "\"",
-                            );
-                        }
+    match value_type {
+        ast::Types::List => collections::parse_list(&value),
 
-                        (ast::Types::Number, ast::Types::String) => {
-                            variables.insert(
-                                "TEMP".to_string(),
-                                "\"".to_owned()
-                                    + &item1.clone()
-                                    + &get_string_content(item2.clone())
-                                    + "\"",
-                            );
-                        }
+        ast::Types::Map => Ok(collections::parse_map(&value)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()),
 
-                        (ast::Types::String, ast::Types::Number) => {
-                            variables.insert(
-                                "TEMP".to_string(),
-                                "\"".to_owned()
-                                    + &get_string_content(item1.clone())
-                                    + &item2.clone()
-                                    + "\"",
-                            );
-                        }
+        ast::Types::String => Ok(get_string_content(value)
+            .lines()
+            .map(|line| "\"".to_owned() + line + "\"")
+            .collect()),
 
-                        (ast::Types::Number, ast::Types::Number) => {
-                            let new_number = item1.clone().parse::<f64>().unwrap()
-                                + item2.clone().parse::<f64>().unwrap();
+        _ => Err(Error::RuntimeError(format!(
+            "Cannot iterate over `{}` as it is not a list, a map or a string.",
+            collection
+        ))),
+    }
+}
 
-                            variables.insert("TEMP".to_string(), format!("{}", new_number));
-                        }
+fn range(args: &[String], variables: &HashMap<String, String>) -> Result<Vec<String>, Error> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(Error::RuntimeError(format!(
+            "Expected 2 or 3 arguments, got {}.",
+            args.len()
+        )));
+    }
 
-                        _ => {
-                            let mut not_supported_arg = args[0].clone();
-                            if get_type(args[0].clone()) != ast::Types::Number
-                                && get_type(args[0].clone()) != ast::Types::String
-                            {
-                                not_supported_arg = args[0].clone();
-                            } else if get_type(args[1].clone()) != ast::Types::Number
-                                && get_type(args[1].clone()) != ast::Types::String
-                            {
-                                not_supported_arg = args[1].clone();
-                            }
-                            print_error(format!("\nCode:\n{} | {}\nProblem: Cannot add as `{}` is neither a string nor a number.", line_number, string_line.clone(), not_supported_arg))
-                        }
-                    }
-                }
+    let mut numbers: Vec<f64> = Vec::new();
+    let mut decimal_places: i32 = 0;
+
+    for arg in args {
+        let (value, value_type) = resolve_value(arg.clone(), variables)?;
+
+        if value_type != ast::Types::Number {
+            return Err(Error::RuntimeError(format!(
+                "`{}` is of type `{}`, but `Number` is required.",
+                arg, value_type
+            )));
+        }
+
+        if let Some((_, decimals)) = value.split_once('.') {
+            decimal_places = decimal_places.max(decimals.len() as i32);
+        }
+
+        numbers.push(value.parse::<f64>().unwrap());
+    }
+
+    let (start, end) = (numbers[0], numbers[1]);
+    let step = if numbers.len() == 3 { numbers[2] } else { 1.0 };
+
+    if step == 0.0 {
+        return Err(Error::RuntimeError("Step cannot be 0.".to_string()));
+    }
+
+    // Every item is worked out from the start instead of adding the step up, so that rounding
+    // errors do not add up, and rounded to the decimal places of the arguments
+    let scale = 10f64.powi(decimal_places.min(15));
+    let mut items: Vec<String> = Vec::new();
+
+    for index in 0.. {
+        let current = ((start + index as f64 * step) * scale).round() / scale;
+
+        if (step > 0.0 && current >= end) || (step < 0.0 && current <= end) {
+            break;
+        }
+
+        items.push(format!("{}", current));
+    }
+
+    Ok(items)
+}
+
+/// Whether `command` stores a value to `TEMP`, which is what `-> <VARIABLE_NAME>` and
+/// expressions use. A label run with `call` gives the value it returns instead.
+fn produces_value(command: &str) -> bool {
+    matches!(
+        command,
+        "add"
+            | "sub"
+            | "mul"
+            | "div"
+            | "mod"
+            | "get_os"
+            | "input"
+            | "to_number"
+            | "to_string"
+            | "read_file"
+            | "is_match"
+            | "count_matches"
+            | "replace_all"
+            | "replace_n"
+            | "find_first"
+            | "find_all"
+            | "captures"
+            | "map_new"
+            | "map_get"
+            | "map_has"
+            | "map_keys"
+            | "map_values"
+            | "map_len"
+            | "eq"
+            | "ne"
+            | "lt"
+            | "le"
+            | "gt"
+            | "ge"
+            | "and"
+            | "or"
+            | "not"
+            | "pow"
+            | "sqrt"
+            | "abs"
+            | "floor"
+            | "ceil"
+            | "round"
+            | "min"
+            | "max"
+            | "clamp"
+            | "sin"
+            | "cos"
+            | "tan"
+            | "ln"
+            | "log10"
+            | "pi"
+            | "e"
+            | "random_int"
+            | "random_float"
+            | "random_choice"
+            | "shuffle"
+            | "len"
+            | "substr"
+            | "index_of"
+            | "contains"
+            | "starts_with"
+            | "ends_with"
+            | "upper"
+            | "lower"
+            | "trim"
+            | "trim_start"
+            | "trim_end"
+            | "repeat"
+            | "reverse"
+            | "pad_left"
+            | "pad_right"
+            | "char_at"
+            | "split"
+            | "split_regex"
+            | "lines"
+            | "split_whitespace"
+            | "join"
+            | "format"
+            | "is_defined"
+            | "typeof"
+            | "eval"
+            | "range"
+    )
+}
+
+/// Runs `command` with `args` as part of line `string_line` and returns the value it stored to
+/// `TEMP`, or the value returned by the label if it is `call`, leaving `TEMP` untouched.
+fn call_command(
+    line_number: usize,
+    string_line: &str,
+    command: &str,
+    args: Vec<String>,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> Result<String, Error> {
+    if command != "call" && !produces_value(command) {
+        return Err(Error::RuntimeError(format!(
+            "Command `{}` does not produce a value.",
+            command
+        )));
+    }
+
+    let label_name = args.first().cloned().unwrap_or_default();
+    let previous_temp = variables.get("TEMP").cloned();
+
+    let mut line = vec![command.to_string()];
+    line.extend(args.into_iter().enumerate().map(|(index, arg)| {
+        // The arguments are values, so braces in them are not placeholders
+        if get_type(arg.clone()) == ast::Types::String && !format::is_format_string(command, index)
+        {
+            format::escape(&arg)
+        } else {
+            arg
+        }
+    }));
+    interpret_line(
+        line_number,
+        line,
+        string_line.to_string(),
+        variables,
+        labels,
+    );
+
+    let result = if command == "call" {
+        scopes::take_returned()
+    } else {
+        variables.get("TEMP").cloned()
+    };
+
+    match previous_temp {
+        Some(temp) => variables.insert("TEMP".to_string(), temp),
+        None => variables.remove("TEMP"),
+    };
+
+    result.ok_or_else(|| {
+        Error::RuntimeError(format!("Label `{}` does not return a value.", label_name))
+    })
+}
+
+/// Runs the code of a label that was jumped to, with its own frame for `local` variables.
+fn run_label(
+    label_name: &str,
+    label_code: Vec<(usize, lexer::Line)>,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> ast::Flow {
+    scopes::enter(label_name, false);
+    errors::enter_label(label_name);
+    let flow = interpret(label_code, variables, labels);
+    errors::leave_label();
+
+    finish_label(flow, false, variables)
+}
+
+/// Runs the code of a label that was called with `call`, with its own frame for `local`
+/// variables in which each of `arguments` is a local variable.
+fn call_label(
+    label_name: &str,
+    label_code: Vec<(usize, lexer::Line)>,
+    arguments: Vec<(String, String)>,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> ast::Flow {
+    scopes::enter(label_name, true);
+    for (name, value) in arguments {
+        scopes::declare(name, value, variables);
+    }
+    errors::enter_label(label_name);
+    let flow = interpret(label_code, variables, labels);
+    errors::leave_label();
+
+    finish_label(flow, true, variables)
+}
+
+// Ends the frame of a label. `return` leaves every label up to the one that was called, or only
+// the label it is in if no label was called.
+fn finish_label(
+    flow: ast::Flow,
+    is_call: bool,
+    variables: &mut HashMap<String, String>,
+) -> ast::Flow {
+    let returned = variables.get("TEMP").cloned();
+    scopes::leave(variables);
+
+    if flow == ast::Flow::Return {
+        // The value given to `return` is kept even if `TEMP` was local to the label
+        if let Some(value) = returned {
+            variables.insert("TEMP".to_string(), value);
+        }
+
+        if is_call || !scopes::in_call() {
+            return ast::Flow::Next;
+        }
+    } else if is_call {
+        // A called label that ends without `return` does not return a value
+        scopes::set_returned(None);
+    }
+
+    flow
+}
+
+/// The parameters of a label, which are given after its name.
+fn label_parameters(label_code: &[(usize, lexer::Line)]) -> Vec<String> {
+    match label_code.first() {
+        Some((_, lexer::Line(line))) if line[0] == "label" => line[2..].to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+fn interpret(
+    lexed_code: Vec<(usize, lexer::Line)>,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> ast::Flow {
+    for (line_number, lexer::Line(line)) in lexed_code.iter() {
+        let flow = interpret_line(
+            *line_number,
+            line.clone(),
+            line.join(" "),
+            variables,
+            labels,
+        );
+
+        if flow != ast::Flow::Next {
+            return flow;
+        }
+    }
+
+    ast::Flow::Next
+}
+
+/// Runs a single line of code, `string_line` being the line as it is shown in errors.
+fn interpret_line(
+    line_number: usize,
+    line: Vec<String>,
+    string_line: String,
+    variables: &mut HashMap<String, String>,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> ast::Flow {
+    let command: String = line[0].clone();
+    let mut args: Vec<String> = line[1..].to_vec().clone();
+
+    if command != "const" {
+        for name in constants::assigned_variables(&line) {
+            if constants::is_constant(&name) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Cannot change `{}` as it is a constant.",
+                    line_number,
+                    string_line.clone(),
+                    name
+                ));
             }
+        }
+    }
 
-            "sub" => {
-                if args_len != 2 {
+    // Strings written in the code can contain `{VARIABLE_NAME}` placeholders
+    for (index, arg) in args.iter_mut().enumerate() {
+        if get_type(arg.clone()) == ast::Types::String && !format::is_format_string(&command, index)
+        {
+            match format::interpolate(&get_string_content(arg.clone()), variables) {
+                Ok(content) => {
+                    *arg = "\"".to_owned() + &content + "\"";
+                }
+                Err(e) => {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: {}",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        e
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
+                }
+            }
+        }
+    }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+    // `<COMMAND> <ARGS> -> <VARIABLE_NAME>` stores the value of the command to
+    // <VARIABLE_NAME> instead of `TEMP`
+    if args.len() >= 2 && args[args.len() - 2] == "->" {
+        let destination = args.pop().unwrap();
+        args.pop();
+
+        if get_type(destination.clone()) != ast::Types::Identifier {
+            print_error(format!(
+                "\nCode:\n{} | {}\nProblem: `{}` is not a valid variable name.",
+                line_number,
+                string_line.clone(),
+                destination
+            ));
+        }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+        match call_command(line_number, &string_line, &command, args, variables, labels) {
+            Ok(value) => {
+                variables.insert(destination, value);
+            }
+            Err(e) => {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    if get_type(item1.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot subtract `{}` from `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[1].clone(), args[0].clone(), args[0].clone()));
-                    }
+        return ast::Flow::Next;
+    }
 
-                    if get_type(item2.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot subtract `{}` from `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[1].clone(), args[0].clone(), args[1].clone()));
-                    }
+    let args_len = args.len();
 
-                    let new_number = item1.clone().parse::<f64>().unwrap()
-                        - item2.clone().parse::<f64>().unwrap();
-                    variables.insert("TEMP".to_string(), format!("{}", new_number));
-                }
-            }
+    match command.clone().as_str() {
+        "exists" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let variable_name = args[0].clone();
 
-            "mul" => {
-                if args_len != 2 {
+                if !variables.contains_key(&variable_name) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Variable `{}` does not exist.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        variable_name
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
+                }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
+                let variable_type = match get_variable(variable_name.clone(), variables) {
+                    Ok((_, variable_type)) => variable_type,
+                    Err(e) => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: {}",
+                            line_number,
+                            string_line.clone(),
+                            e
+                        ));
+                        return ast::Flow::Next;
                     }
+                };
+                let variable_required_type = string_to_type(args[1].clone());
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                match variable_required_type.clone() {
+                    Ok(required_type) => {
+                        if variable_type != required_type {
+                            print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `{}` is required.", line_number, string_line.clone(), variable_name, variable_type, required_type));
                         }
                     }
-
-                    if get_type(item1.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot multiply `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
-                    }
-
-                    if get_type(item2.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot multiply `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
+                    Err(e) => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: {}",
+                            line_number,
+                            string_line.clone(),
+                            e
+                        ));
                     }
-
-                    let new_number = item1.clone().parse::<f64>().unwrap()
-                        * item2.clone().parse::<f64>().unwrap();
-                    variables.insert("TEMP".to_string(), format!("{}", new_number));
                 }
             }
+        }
 
-            "div" => {
-                if args_len != 2 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
+        "var" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let variable_name = args[0].clone();
+                let mut variable_value = args[1].clone();
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                if get_type(variable_value.clone()) == ast::Types::Identifier {
+                    match get_variable(variable_value.clone(), variables) {
+                        Ok((value, _)) => {
+                            variable_value = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
+
+                variables.insert(variable_name.clone(), variable_value.clone());
+            }
+        }
+
+        "print" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut to_print = args[0].clone();
+
+                match get_type(to_print.clone()) {
+                    ast::Types::Number | ast::Types::Bool | ast::Types::Nil => {}
+
+                    ast::Types::String => {
+                        to_print = get_string_content(to_print.clone());
+                    }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item2 = value;
+                    // Variables
+                    ast::Types::Identifier => {
+                        match get_variable(to_print.clone(), variables) {
+                            Ok((value, _)) => {
+                                to_print = value;
                             }
                             Err(e) => {
                                 print_error(format!(
@@ -502,413 +936,562 @@ fn interpret(
                                 ));
                             }
                         }
-                    }
 
-                    if get_type(item1.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot divide `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
-                    }
+                        let to_print_type = get_type(to_print.clone());
 
-                    if get_type(item2.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot divide `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
+                        if to_print_type == ast::Types::String {
+                            to_print = get_string_content(to_print.clone());
+                        }
                     }
 
-                    let new_number = item1.clone().parse::<f64>().unwrap()
-                        / item2.clone().parse::<f64>().unwrap();
-                    variables.insert("TEMP".to_string(), format!("{}", new_number));
+                    _ => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: Invalid type.",
+                            line_number,
+                            string_line.clone()
+                        ));
+                    }
                 }
-            }
 
-            "mod" => {
-                if args_len != 2 {
+                if let Err(e) = output::write(&to_print) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: {}",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        e
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
+                }
+            }
+        }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+        "print_newline" => {
+            if args_len != 0 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else if let Err(e) = output::write("\n") {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "add" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item1 = args[0].clone();
+                let mut item2 = args[1].clone();
+
+                if get_type(item1.clone()) == ast::Types::Identifier {
+                    match get_variable(item1.clone(), variables) {
+                        Ok((value, _)) => {
+                            item1 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                if get_type(item2.clone()) == ast::Types::Identifier {
+                    match get_variable(item2.clone(), variables) {
+                        Ok((value, _)) => {
+                            item2 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
+
+                match (get_type(item1.clone()), get_type(item2.clone())) {
+                    (ast::Types::String, ast::Types::String) => {
+                        variables.insert(
+                            "TEMP".to_string(),
+                            "\"".to_owned()
+                                + &get_string_content(item1.clone())
+                                + &get_string_content(item2.clone())
+                                + "\"",
+                        );
+                    }
 
-                    if get_type(item1.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
+                    (ast::Types::Number, ast::Types::String) => {
+                        variables.insert(
+                            "TEMP".to_string(),
+                            "\"".to_owned()
+                                + &item1.clone()
+                                + &get_string_content(item2.clone())
+                                + "\"",
+                        );
                     }
 
-                    if get_type(item2.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
+                    (ast::Types::String, ast::Types::Number) => {
+                        variables.insert(
+                            "TEMP".to_string(),
+                            "\"".to_owned()
+                                + &get_string_content(item1.clone())
+                                + &item2.clone()
+                                + "\"",
+                        );
                     }
 
-                    let new_number = item1.clone().parse::<f64>().unwrap()
-                        % item2.clone().parse::<f64>().unwrap();
-                    variables.insert("TEMP".to_string(), format!("{}", new_number));
+                    (ast::Types::Number, ast::Types::Number) => {
+                        let new_number = item1.clone().parse::<f64>().unwrap()
+                            + item2.clone().parse::<f64>().unwrap();
+
+                        if let Err(e) = math::check_finite(&command, new_number) {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
+                        }
+                        variables.insert("TEMP".to_string(), format!("{}", new_number));
+                    }
+
+                    _ => {
+                        let mut not_supported_arg = args[0].clone();
+                        if get_type(args[0].clone()) != ast::Types::Number
+                            && get_type(args[0].clone()) != ast::Types::String
+                        {
+                            not_supported_arg = args[0].clone();
+                        } else if get_type(args[1].clone()) != ast::Types::Number
+                            && get_type(args[1].clone()) != ast::Types::String
+                        {
+                            not_supported_arg = args[1].clone();
+                        }
+                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot add as `{}` is neither a string nor a number.", line_number, string_line.clone(), not_supported_arg))
+                    }
                 }
             }
+        }
 
-            "jmp" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let label_name = args[0].clone();
+        "sub" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item1 = args[0].clone();
+                let mut item2 = args[1].clone();
 
-                    if !labels.contains_key(&label_name) {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
-                            line_number,
-                            string_line.clone(),
-                            label_name
-                        ));
+                if get_type(item1.clone()) == ast::Types::Identifier {
+                    match get_variable(item1.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
+                            }
+                            item1 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
+                        }
+                    }
+                }
+
+                if get_type(item2.clone()) == ast::Types::Identifier {
+                    match get_variable(item2.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
+                            }
+                            item2 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
+                        }
                     }
+                }
 
-                    let label_code = labels.get(&label_name).unwrap().clone();
+                if get_type(item1.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot subtract `{}` from `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[1].clone(), args[0].clone(), args[0].clone()));
+                }
 
-                    interpret(label_code, variables, labels);
+                if get_type(item2.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot subtract `{}` from `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[1].clone(), args[0].clone(), args[1].clone()));
                 }
-            }
 
-            "jmp_gt" => {
-                if args_len != 3 {
+                let new_number =
+                    item1.clone().parse::<f64>().unwrap() - item2.clone().parse::<f64>().unwrap();
+                if let Err(e) = math::check_finite(&command, new_number) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: {}",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        e
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
-                    let label_name = args[2].clone();
+                }
+                variables.insert("TEMP".to_string(), format!("{}", new_number));
+            }
+        }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+        "mul" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item1 = args[0].clone();
+                let mut item2 = args[1].clone();
+
+                if get_type(item1.clone()) == ast::Types::Identifier {
+                    match get_variable(item1.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item1 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item2, value_type));
-                                }
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                if get_type(item2.clone()) == ast::Types::Identifier {
+                    match get_variable(item2.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item2 = value;
                         }
-                    }
-
-                    if item1.parse::<f64>().unwrap() > item2.parse::<f64>().unwrap() {
-                        if !labels.contains_key(&label_name) {
+                        Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                label_name
+                                e
                             ));
                         }
+                    }
+                }
 
-                        let label_code = labels.get(&label_name).unwrap().clone();
+                if get_type(item1.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot multiply `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
+                }
 
-                        interpret(label_code, variables, labels);
-                    }
+                if get_type(item2.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot multiply `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
                 }
-            }
 
-            "jmp_lt" => {
-                if args_len != 3 {
+                let new_number =
+                    item1.clone().parse::<f64>().unwrap() * item2.clone().parse::<f64>().unwrap();
+                if let Err(e) = math::check_finite(&command, new_number) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: {}",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        e
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item2 = args[1].clone();
-                    let label_name = args[2].clone();
+                }
+                variables.insert("TEMP".to_string(), format!("{}", new_number));
+            }
+        }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
-                                }
-                                item1 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+        "div" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item1 = args[0].clone();
+                let mut item2 = args[1].clone();
+
+                if get_type(item1.clone()) == ast::Types::Identifier {
+                    match get_variable(item1.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item1 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                if value_type != ast::Types::Number {
-                                    print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item2, value_type));
-                                }
-                                item2 = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                if get_type(item2.clone()) == ast::Types::Identifier {
+                    match get_variable(item2.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item2 = value;
                         }
-                    }
-
-                    if get_type(item1.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
-                    }
-
-                    if get_type(item2.clone()) != ast::Types::Number {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
-                    }
-
-                    if item1.parse::<f64>().unwrap() < item2.parse::<f64>().unwrap() {
-                        if !labels.contains_key(&label_name) {
+                        Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                label_name
+                                e
                             ));
                         }
+                    }
+                }
 
-                        let label_code = labels.get(&label_name).unwrap().clone();
+                if get_type(item1.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot divide `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
+                }
 
-                        interpret(label_code, variables, labels);
-                    }
+                if get_type(item2.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot divide `{}` by `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
+                }
+
+                if item2.parse::<f64>().unwrap() == 0.0 {
+                    print_error(format!(
+                        "\nCode:\n{} | {}\nProblem: Cannot divide `{}` by zero.",
+                        line_number,
+                        string_line.clone(),
+                        args[0].clone()
+                    ));
                 }
-            }
 
-            "jmp_eq" => {
-                if args_len != 3 {
+                let new_number =
+                    item1.clone().parse::<f64>().unwrap() / item2.clone().parse::<f64>().unwrap();
+                if let Err(e) = math::check_finite(&command, new_number) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: {}",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        e
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item1_type: ast::Types = get_type(item1.clone());
-
-                    let mut item2 = args[1].clone();
-                    let mut item2_type: ast::Types = get_type(item2.clone());
+                }
+                variables.insert("TEMP".to_string(), format!("{}", new_number));
+            }
+        }
 
-                    let label_name = args[2].clone();
+        "mod" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item1 = args[0].clone();
+                let mut item2 = args[1].clone();
 
-                    if item1_type.clone() == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                item1 = value;
-                                item1_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                if get_type(item1.clone()) == ast::Types::Identifier {
+                    match get_variable(item1.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item1 = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if item2_type.clone() == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                item2 = value;
-                                item2_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                if get_type(item2.clone()) == ast::Types::Identifier {
+                    match get_variable(item2.clone(), variables) {
+                        Ok((value, value_type)) => {
+                            if value_type != ast::Types::Number {
+                                print_error(format!("\nCode:\n{} | {}\nProblem: Variable `{}` is of type `{}`, but `Number` is required.", line_number, string_line.clone(), item1, value_type));
                             }
+                            item2 = value;
                         }
-                    }
-
-                    if item1_type != item2_type {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as they are not the same type.", line_number, string_line.clone(), args[0].clone(), args[1].clone()));
-                    }
-
-                    if item1 == item2 {
-                        if !labels.contains_key(&label_name) {
+                        Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                label_name
+                                e
                             ));
                         }
+                    }
+                }
 
-                        let label_code = labels.get(&label_name).unwrap().clone();
+                if get_type(item1.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[0].clone()));
+                }
 
-                        interpret(label_code, variables, labels);
-                    }
+                if get_type(item2.clone()) != ast::Types::Number {
+                    print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as `{}` is not a numeric type.", line_number, string_line.clone(), args[0].clone(), args[1].clone(), args[1].clone()));
+                }
+
+                if item2.parse::<f64>().unwrap() == 0.0 {
+                    print_error(format!(
+                        "\nCode:\n{} | {}\nProblem: Cannot get the remainder of `{}` by zero.",
+                        line_number,
+                        string_line.clone(),
+                        args[0].clone()
+                    ));
                 }
+
+                let new_number =
+                    item1.clone().parse::<f64>().unwrap() % item2.clone().parse::<f64>().unwrap();
+                if let Err(e) = math::check_finite(&command, new_number) {
+                    print_error(format!(
+                        "\nCode:\n{} | {}\nProblem: {}",
+                        line_number,
+                        string_line.clone(),
+                        e
+                    ));
+                }
+                variables.insert("TEMP".to_string(), format!("{}", new_number));
             }
+        }
+
+        "jmp" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let label_name = args[0].clone();
 
-            "jmp_not_eq" => {
-                if args_len != 3 {
+                if !labels.contains_key(&label_name) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        label_name
                     ));
-                } else {
-                    let mut item1 = args[0].clone();
-                    let mut item1_type: ast::Types = ast::Types::Number;
+                }
 
-                    let mut item2 = args[1].clone();
-                    let mut item2_type: ast::Types = ast::Types::Number;
+                let label_code = labels.get(&label_name).unwrap().clone();
 
-                    let label_name = args[2].clone();
+                let flow = run_label(&label_name, label_code, variables, labels);
+                if flow != ast::Flow::Next {
+                    return flow;
+                }
+            }
+        }
 
-                    if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                item1 = value;
-                                item1_type = value_type;
-                            }
-                            Err(e) => {
+        "jmp_gt" | "jmp_lt" | "jmp_ge" | "jmp_le" | "jmp_eq" | "jmp_not_eq" => {
+            if args_len != 3 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let label_name = args[2].clone();
+
+                let ordering_only = command != "jmp_eq" && command != "jmp_not_eq";
+
+                match compare_values(args[0].clone(), args[1].clone(), variables, ordering_only) {
+                    Ok(ordering) => {
+                        let should_jump = match command.as_str() {
+                            "jmp_gt" => ordering == std::cmp::Ordering::Greater,
+                            "jmp_lt" => ordering == std::cmp::Ordering::Less,
+                            "jmp_ge" => ordering != std::cmp::Ordering::Less,
+                            "jmp_le" => ordering != std::cmp::Ordering::Greater,
+                            "jmp_eq" => ordering == std::cmp::Ordering::Equal,
+                            _ => ordering != std::cmp::Ordering::Equal,
+                        };
+
+                        if should_jump {
+                            if !labels.contains_key(&label_name) {
                                 print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
+                                    "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
                                     line_number,
                                     string_line.clone(),
-                                    e
+                                    label_name
                                 ));
                             }
-                        }
-                    }
 
-                    if get_type(item2.clone()) == ast::Types::Identifier {
-                        match get_variable(item2.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                item2 = value;
-                                item2_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                            let label_code = labels.get(&label_name).unwrap().clone();
+
+                            let flow = run_label(&label_name, label_code, variables, labels);
+                            if flow != ast::Flow::Next {
+                                return flow;
                             }
                         }
                     }
-
-                    if item1_type != item2_type {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot compare `{}` and `{}` as they are not the same type.", line_number, string_line.clone(), args[0].clone(), args[1].clone()));
-                    }
-
-                    if item1 != item2 {
-                        if !labels.contains_key(&label_name) {
-                            print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
-                                line_number,
-                                string_line.clone(),
-                                label_name
-                            ));
-                        }
-
-                        let label_code = labels.get(&label_name).unwrap().clone();
-
-                        interpret(label_code, variables, labels);
+                    Err(e) => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: {}",
+                            line_number,
+                            string_line.clone(),
+                            e
+                        ));
                     }
                 }
             }
+        }
 
-            "return" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
+        "return" => {
+            if args_len > 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 0 or 1 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                if args_len == 1 {
                     let mut item1 = args[0].clone();
 
                     if get_type(item1.clone()) == ast::Types::Identifier {
-                        match get_variable(item1.clone(), variables.clone()) {
+                        match get_variable(item1.clone(), variables) {
                             Ok((value, _)) => {
                                 item1 = value;
                             }
@@ -923,713 +1506,1008 @@ fn interpret(
                         }
                     }
 
-                    variables.insert("TEMP".to_string(), item1);
+                    variables.insert("TEMP".to_string(), item1.clone());
+                    scopes::set_returned(Some(item1));
+                } else {
+                    scopes::set_returned(None);
                 }
+
+                return ast::Flow::Return;
             }
+        }
+
+        "call" => {
+            if args_len == 0 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected at least 1 argument, got 0.",
+                    line_number,
+                    string_line.clone()
+                ));
+            } else {
+                let label_name = args[0].clone();
 
-            "get_os" => {
-                if args_len != 0 {
+                if !labels.contains_key(&label_name) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        label_name
                     ));
-                } else {
-                    variables.insert("TEMP".to_string(), env::consts::OS.to_string());
                 }
-            }
 
-            "cmd" => {
-                if args_len != 1 {
+                let label_code = labels.get(&label_name).unwrap().clone();
+                let parameters = label_parameters(&label_code);
+
+                if parameters.len() != args_len - 1 {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Label `{}` takes {} argument{}, got {}.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        label_name,
+                        parameters.len(),
+                        if parameters.len() == 1 { "" } else { "s" },
+                        args_len - 1
                     ));
-                } else {
-                    let mut cmd = args[0].clone();
+                }
 
-                    if get_type(cmd.clone()) == ast::Types::Identifier {
-                        match get_variable(cmd.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                cmd = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                let mut arguments: Vec<(String, String)> = Vec::new();
+                for (parameter, arg) in parameters.into_iter().zip(args[1..].iter()) {
+                    match resolve_value(arg.clone(), variables) {
+                        Ok((value, _)) => arguments.push((parameter, value)),
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if get_type(cmd.clone()) != ast::Types::String {
+                match call_label(&label_name, label_code, arguments, variables, labels) {
+                    ast::Flow::Break | ast::Flow::Continue => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot execute `{}` as it is not a string.",
+                            "\nCode:\n{} | {}\nProblem: `break` or `continue` cannot leave label `{}` as it was run with `call`.",
                             line_number,
                             string_line.clone(),
-                            cmd
+                            label_name
                         ));
                     }
+                    flow if flow != ast::Flow::Next => return flow,
+                    _ => {}
+                }
+            }
+        }
 
-                    let cmd = cmd.split(' ').collect::<Vec<&str>>();
-
-                    let mut command_to_execute = Command::new(cmd[0]);
+        // The first line of a label with parameters, which does nothing when it runs
+        "label" => {}
 
-                    for i in cmd.iter().skip(1) {
-                        command_to_execute.arg(i);
-                    }
+        "get_os" => {
+            if args_len != 0 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                variables.insert("TEMP".to_string(), "\"".to_owned() + env::consts::OS + "\"");
+            }
+        }
 
-                    match command_to_execute.output() {
-                        Ok(_) => {}
+        "cmd" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut cmd = args[0].clone();
 
+                if get_type(cmd.clone()) == ast::Types::Identifier {
+                    match get_variable(cmd.clone(), variables) {
+                        Ok((value, _)) => {
+                            cmd = value;
+                        }
                         Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Failed to execute command `{}`: {}",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                get_string_content(cmd.join(" ").clone()),
                                 e
                             ));
                         }
                     }
                 }
-            }
 
-            "input" => {
-                if args_len != 0 {
+                if get_type(cmd.clone()) != ast::Types::String {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Cannot execute `{}` as it is not a string.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        cmd
                     ));
-                } else {
-                    let mut input = String::new();
+                }
 
-                    match std::io::stdout().flush() {
-                        Ok(_) => {}
+                let cmd = cmd.split(' ').collect::<Vec<&str>>();
 
-                        Err(e) => {
-                            print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Failed to flush stdout: {}",
-                                line_number,
-                                string_line.clone(),
-                                e
-                            ));
-                        }
-                    }
-                    std::io::stdin().read_line(&mut input).unwrap();
+                let mut command_to_execute = Command::new(cmd[0]);
 
-                    variables.insert(
-                        "TEMP".to_string(),
-                        "\"".to_owned() + &input.trim().to_owned() + "\"",
-                    );
+                for i in cmd.iter().skip(1) {
+                    command_to_execute.arg(i);
                 }
-            }
 
-            "to_number" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item = args[0].clone();
+                match command_to_execute.output() {
+                    Ok(_) => {}
 
-                    if get_type(item.clone()) == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
+                    Err(e) => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: Failed to execute command `{}`: {}",
+                            line_number,
+                            string_line.clone(),
+                            get_string_content(cmd.join(" ").clone()),
+                            e
+                        ));
                     }
+                }
+            }
+        }
+
+        "input" => {
+            if args_len != 0 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut input = String::new();
 
-                    if get_type(item.clone()) == ast::Types::String {
-                        item = get_string_content(item.clone());
+                match std::io::stdout().flush() {
+                    Ok(_) => {}
+
+                    Err(e) => {
+                        print_error(format!(
+                            "\nCode:\n{} | {}\nProblem: Failed to flush stdout: {}",
+                            line_number,
+                            string_line.clone(),
+                            e
+                        ));
                     }
+                }
+                std::io::stdin().read_line(&mut input).unwrap();
 
-                    match item.parse::<f64>() {
-                        Ok(_) => {
-                            variables.insert("TEMP".to_string(), item.to_string());
+                variables.insert("TEMP".to_string(), "\"".to_owned() + input.trim() + "\"");
+            }
+        }
+
+        "to_number" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item = args[0].clone();
+
+                if get_type(item.clone()) == ast::Types::Identifier {
+                    match get_variable(item.clone(), variables) {
+                        Ok((value, _)) => {
+                            item = value;
                         }
-                        Err(_) => {
+                        Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Cannot convert `{}` to a number.",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                item
+                                e
                             ));
                         }
                     }
-                }
-            }
-
-            "to_string" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item = args[0].clone();
-
-                    if get_type(item.clone()) == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+                }
+
+                if get_type(item.clone()) == ast::Types::String {
+                    item = get_string_content(item.clone());
+                }
 
-                    let item_type = get_type(item.clone());
+                match item.parse::<f64>() {
+                    Ok(number) => {
+                        if let Err(e) = math::check_finite(&command, number) {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
+                        }
 
-                    if item_type.clone() == ast::Types::String {
-                        // Do nothing
-                    } else if item_type.clone() == ast::Types::Number {
-                        item = "\"".to_owned() + &item.to_string() + "\"";
-                    } else {
+                        // Written the way every number is, so `1e3` becomes `1000`
+                        variables.insert("TEMP".to_string(), format!("{}", number));
+                    }
+                    Err(_) => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot convert `{}` to a string.",
+                            "\nCode:\n{} | {}\nProblem: Cannot convert `{}` to a number.",
                             line_number,
                             string_line.clone(),
                             item
                         ));
                     }
-
-                    variables.insert("TEMP".to_string(), item);
                 }
             }
+        }
 
-            "read_file" => {
-                if args_len != 1 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                        line_number,
-                        string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item = args[0].clone();
+        "to_string" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item = args[0].clone();
 
-                    if get_type(item.clone()) == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                if get_type(item.clone()) == ast::Types::Identifier {
+                    match get_variable(item.clone(), variables) {
+                        Ok((value, _)) => {
+                            item = value;
+                        }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: {}",
+                                line_number,
+                                string_line.clone(),
+                                e
+                            ));
                         }
                     }
+                }
 
-                    if get_type(item.clone()) != ast::Types::String {
-                        print_error(format!("\nCode:\n{} | {}\nProblem: Cannot read file `{}` as it is not a string.", line_number, string_line.clone(), item));
-                    }
+                let item_type = get_type(item.clone());
+
+                if item_type.clone() == ast::Types::String {
+                    // Do nothing
+                } else if item_type.clone() == ast::Types::Number {
+                    item = "\"".to_owned() + &item.to_string() + "\"";
+                } else {
+                    print_error(format!(
+                        "\nCode:\n{} | {}\nProblem: Cannot convert `{}` to a string.",
+                        line_number,
+                        string_line.clone(),
+                        item
+                    ));
+                }
 
-                    let mut contents = String::new();
+                variables.insert("TEMP".to_string(), item);
+            }
+        }
 
-                    match File::open(get_string_content(item.clone())) {
-                        Ok(mut file) => match file.read_to_string(&mut contents) {
-                            Ok(_) => {
-                                variables
-                                    .insert("TEMP".to_string(), "\"".to_owned() + &contents + "\"");
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: Failed to read file `{}`: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    item,
-                                    e
-                                ));
-                            }
-                        },
+        "read_file" => {
+            if args_len != 1 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let mut item = args[0].clone();
 
+                if get_type(item.clone()) == ast::Types::Identifier {
+                    match get_variable(item.clone(), variables) {
+                        Ok((value, _)) => {
+                            item = value;
+                        }
                         Err(e) => {
                             print_error(format!(
-                                "\nCode:\n{} | {}\nProblem: Failed to open file `{}`: {}",
+                                "\nCode:\n{} | {}\nProblem: {}",
                                 line_number,
                                 string_line.clone(),
-                                item,
                                 e
                             ));
                         }
                     }
                 }
-            }
 
-            "is_match" => {
-                if args_len != 2 {
+                if get_type(item.clone()) != ast::Types::String {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Cannot read file `{}` as it is not a string.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        item
                     ));
-                } else {
-                    let mut item = args[0].clone();
-                    let item_type = get_type(item.clone());
+                }
 
-                    if item_type.clone() == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+                let mut contents = String::new();
+
+                match File::open(get_string_content(item.clone())) {
+                    Ok(mut file) => match file.read_to_string(&mut contents) {
+                        Ok(_) => {
+                            variables
+                                .insert("TEMP".to_string(), "\"".to_owned() + &contents + "\"");
                         }
-                    }
+                        Err(e) => {
+                            print_error(format!(
+                                "\nCode:\n{} | {}\nProblem: Failed to read file `{}`: {}",
+                                line_number,
+                                string_line.clone(),
+                                item,
+                                e
+                            ));
+                        }
+                    },
 
-                    if item_type.clone() != ast::Types::String {
+                    Err(e) => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot check if `{}` is a match for `{}` as it is not a string.",
+                            "\nCode:\n{} | {}\nProblem: Failed to open file `{}`: {}",
                             line_number,
                             string_line.clone(),
                             item,
-                            args[1].clone()
+                            e
                         ));
                     }
+                }
+            }
+        }
+
+        "is_match" | "count_matches" | "replace_all" | "replace_n" | "find_first" | "find_all"
+        | "captures" => {
+            if let Err(e) = regexes::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    let mut pattern = args[1].clone();
-                    let mut pattern_type = get_type(pattern.clone());
+        "map_new" | "map_set" | "map_get" | "map_has" | "map_remove" | "map_keys"
+        | "map_values" | "map_len" => {
+            if let Err(e) = map_command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    if pattern_type == ast::Types::Identifier {
-                        match get_variable(pattern.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                pattern = value;
-                                pattern_type = value_type;
-                            }
-                            Err(e) => {
+        "eq" | "ne" | "lt" | "le" | "gt" | "ge" | "and" | "or" | "not" => {
+            if let Err(e) = logic_command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "jmp_if" | "jmp_unless" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let label_name = args[1].clone();
+
+                match resolve_bool(args[0].clone(), variables) {
+                    Ok(condition) => {
+                        if condition == (command == "jmp_if") {
+                            if !labels.contains_key(&label_name) {
                                 print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
+                                    "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
                                     line_number,
                                     string_line.clone(),
-                                    e
+                                    label_name
                                 ));
                             }
+
+                            let label_code = labels.get(&label_name).unwrap().clone();
+
+                            let flow = run_label(&label_name, label_code, variables, labels);
+                            if flow != ast::Flow::Next {
+                                return flow;
+                            }
                         }
                     }
-
-                    if pattern_type != ast::Types::String {
+                    Err(e) => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not of type String.",
+                            "\nCode:\n{} | {}\nProblem: {}",
                             line_number,
                             string_line.clone(),
-                            args[1].clone()
+                            e
                         ));
                     }
+                }
+            }
+        }
 
-                    pattern = get_string_content(pattern);
+        "pow" | "sqrt" | "abs" | "floor" | "ceil" | "round" | "min" | "max" | "clamp" | "sin"
+        | "cos" | "tan" | "ln" | "log10" | "pi" | "e" => {
+            if let Err(e) = math::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    if let Ok(re) = Regex::new(&pattern) {
-                        if re.is_match(&item) {
-                            variables.insert("TEMP".to_string(), "1".to_string());
-                        } else {
-                            variables.insert("TEMP".to_string(), "0".to_string());
-                        }
-                    } else {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not a valid regular expression.",
-                            line_number,
-                            string_line.clone(),
-                            args[1].clone()
-                        ));
+        "random_seed" | "random_int" | "random_float" | "random_choice" | "shuffle" => {
+            if let Err(e) = random::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "len" | "substr" | "index_of" | "contains" | "starts_with" | "ends_with" | "upper"
+        | "lower" | "trim" | "trim_start" | "trim_end" | "repeat" | "reverse" | "pad_left"
+        | "pad_right" | "char_at" | "split" | "split_regex" | "lines" | "split_whitespace"
+        | "join" => {
+            if let Err(e) = strings::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "printf" | "format" | "println" => {
+            if let Err(e) = format::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "eprint" | "eprintln" | "redirect_output" | "end_redirect" => {
+            if let Err(e) = output::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "local" | "global" => {
+            if let Err(e) = scopes::command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
+
+        "alias" => {
+            if args_len != 2 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else if get_type(args[0].clone()) != ast::Types::Identifier
+                || get_type(args[1].clone()) != ast::Types::Identifier
+            {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: `alias` takes two variable names.",
+                    line_number,
+                    string_line.clone()
+                ));
+            } else {
+                // The variable referred to may not exist yet, but it cannot lead back
+                let mut target = args[1].clone();
+                let mut steps = 0;
+
+                while target != args[0] && steps <= variables.len() {
+                    match variables.get(&target).and_then(|value| alias_target(value)) {
+                        Some(next) => target = next.to_string(),
+                        None => break,
                     }
+                    steps += 1;
                 }
-            }
 
-            "count_matches" => {
-                if args_len != 2 {
+                if target == args[0] {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 2 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Alias `{}` would refer back to itself.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        args[0]
                     ));
-                } else {
-                    let mut item = args[0].clone();
-                    let item_type = get_type(item.clone());
+                }
 
-                    if item_type.clone() == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+                variables.insert(args[0].clone(), ALIAS_PREFIX.to_owned() + &args[1]);
+            }
+        }
 
-                    if item_type.clone() != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot check if `{}` is a match for `{}` as it is not a string.",
-                            line_number,
-                            string_line.clone(),
-                            item,
-                            args[1].clone()
-                        ));
-                    }
+        "unset" | "is_defined" | "typeof" => {
+            if let Err(e) = variable_command(&command, &args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    let mut pattern = args[1].clone();
-                    let mut pattern_type = get_type(pattern.clone());
+        "const" => {
+            if let Err(e) = constants::command(&args, variables) {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        }
 
-                    if pattern_type == ast::Types::Identifier {
-                        match get_variable(pattern.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                pattern = value;
-                                pattern_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+        "eval" => match expression::evaluate(&args, line_number, &string_line, variables, labels) {
+            Ok(value) => {
+                variables.insert("TEMP".to_string(), value);
+            }
+            Err(e) => {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        },
 
-                    if pattern_type != ast::Types::String {
+        "let" => {
+            if args_len < 3 || args[1] != "=" {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected `let <VARIABLE_NAME> = <EXPRESSION>`.",
+                    line_number,
+                    string_line.clone()
+                ));
+            } else if get_type(args[0].clone()) != ast::Types::Identifier {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: `{}` is not a valid variable name.",
+                    line_number,
+                    string_line.clone(),
+                    args[0]
+                ));
+            } else {
+                match expression::evaluate(&args[2..], line_number, &string_line, variables, labels)
+                {
+                    Ok(value) => {
+                        variables.insert(args[0].clone(), value);
+                    }
+                    Err(e) => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not of type String.",
+                            "\nCode:\n{} | {}\nProblem: {}",
                             line_number,
                             string_line.clone(),
-                            args[1].clone()
+                            e
                         ));
                     }
+                }
+            }
+        }
 
-                    pattern = get_string_content(pattern);
+        "foreach" => {
+            if args_len != 3 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else {
+                let variable_name = args[0].clone();
+                let label_name = args[2].clone();
 
-                    if let Ok(re) = Regex::new(&pattern) {
-                        variables.insert("TEMP".to_string(), re.find_iter(&item).count().to_string());
-                    } else {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not a valid regular expression.",
-                            line_number,
-                            string_line.clone(),
-                            args[1].clone()
-                        ));
-                    }
+                if get_type(variable_name.clone()) != ast::Types::Identifier {
+                    print_error(format!(
+                        "\nCode:\n{} | {}\nProblem: `{}` is not a valid variable name.",
+                        line_number,
+                        string_line.clone(),
+                        variable_name
+                    ));
                 }
-            }
 
-            "replace_n" => {
-                if args_len != 4 {
+                if !labels.contains_key(&label_name) {
                     print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+                        "\nCode:\n{} | {}\nProblem: Label `{}` does not exist.",
                         line_number,
                         string_line.clone(),
-                        args_len
+                        label_name
                     ));
-                } else {
-                    let mut item = args[0].clone();
-                    let item_type = get_type(item.clone());
+                }
 
-                    if item_type.clone() == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
+                match foreach_items(args[1].clone(), variables) {
+                    Ok(items) => {
+                        let label_code = labels.get(&label_name).unwrap().clone();
+
+                        for item in items {
+                            variables.insert(variable_name.clone(), item);
+
+                            match run_label(&label_name, label_code.clone(), variables, labels) {
+                                ast::Flow::Break => break,
+                                ast::Flow::Return => return ast::Flow::Return,
+                                _ => {}
                             }
                         }
                     }
-
-                    if item_type.clone() != ast::Types::String {
+                    Err(e) => {
                         print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot check if `{}` is a match for `{}` as it is not a string.",
+                            "\nCode:\n{} | {}\nProblem: {}",
                             line_number,
                             string_line.clone(),
-                            item,
-                            args[1].clone()
+                            e
                         ));
                     }
+                }
+            }
+        }
+
+        "range" => match range(&args, variables) {
+            Ok(items) => {
+                variables.insert("TEMP".to_string(), collections::make_list(&items));
+            }
+            Err(e) => {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number,
+                    string_line.clone(),
+                    e
+                ));
+            }
+        },
 
-                    let mut pattern = args[1].clone();
-                    let mut pattern_type = get_type(pattern.clone());
+        "break" | "continue" => {
+            if args_len != 0 {
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: Expected 0 arguments, got {}.",
+                    line_number,
+                    string_line.clone(),
+                    args_len
+                ));
+            } else if command == "break" {
+                return ast::Flow::Break;
+            } else {
+                return ast::Flow::Continue;
+            }
+        }
 
-                    if pattern_type == ast::Types::Identifier {
-                        match get_variable(pattern.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                pattern = value;
-                                pattern_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+        "comment" => {
+            // Do nothing
+        }
 
-                    if pattern_type != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not of type String.",
-                            line_number,
-                            string_line.clone(),
-                            args[1].clone()
-                        ));
-                    }
+        _ => {
+            print_error(format!(
+                "\nCode:\n{} | {}\nProblem: Unknown command `{}`.",
+                line_number,
+                string_line.clone(),
+                command.clone()
+            ));
+        }
+    }
 
-                    pattern = get_string_content(pattern.clone());
+    ast::Flow::Next
+}
 
-                    let mut replacement = args[2].clone();
-                    let mut replacement_type = get_type(replacement.clone());
+/// The position of the label name among the arguments of `command`, if it takes one.
+fn label_argument(command: &str) -> Option<usize> {
+    match command {
+        "jmp" | "call" => Some(0),
+        "jmp_if" | "jmp_unless" => Some(1),
+        "jmp_gt" | "jmp_lt" | "jmp_ge" | "jmp_le" | "jmp_eq" | "jmp_not_eq" | "foreach" => Some(2),
+        _ => None,
+    }
+}
 
-                    if replacement_type == ast::Types::Identifier {
-                        match get_variable(replacement.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                replacement = value;
-                                replacement_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+/// Checks, before the script runs, that no label with parameters is jumped to, as only `call`
+/// gives them values. On failure, returns the label, line number and line along with the problem.
+fn check_jumps(
+    labels: &HashMap<String, Vec<(usize, lexer::Line)>>,
+) -> Result<(), (String, usize, String, Error)> {
+    for (label_name, code) in labels {
+        for (line_number, lexer::Line(line)) in code {
+            if line[0] == "call" {
+                continue;
+            }
 
-                    if replacement_type != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Replacement `{}` is not of type String.",
-                            line_number,
-                            string_line.clone(),
-                            args[2].clone()
-                        ));
-                    }
+            let target = label_argument(&line[0]).and_then(|index| line.get(index + 1));
+
+            if let Some(target) = target {
+                if !label_parameters(labels.get(target).map_or(&[], |code| code)).is_empty() {
+                    return Err((
+                        label_name.clone(),
+                        *line_number,
+                        line.join(" "),
+                        Error::RuntimeError(format!(
+                            "Label `{}` takes parameters, so it can only be run with `call`.",
+                            target
+                        )),
+                    ));
+                }
+            }
+        }
+    }
 
-                    replacement = get_string_content(replacement);
+    Ok(())
+}
 
-                    let mut count = args[3].clone();
-                    let mut count_type = get_type(count.clone());
+/// Reads the script at `path` and adds its labels to `labels` with `namespace` in front of their
+/// names, along with the labels of the scripts it imports.
+///
+/// `importing` holds the scripts whose imports are being read, as they were written and as
+/// absolute paths, to find import cycles.
+fn load_script(
+    path: &Path,
+    namespace: &str,
+    labels: &mut HashMap<String, Vec<(usize, lexer::Line)>>,
+    importing: &mut Vec<(String, PathBuf)>,
+) {
+    // Problems in imported scripts say which script they are in
+    let located = |message: String| {
+        if namespace.is_empty() {
+            message
+        } else {
+            format!("\nFile: {}{}", path.display(), message)
+        }
+    };
 
-                    if count_type == ast::Types::Identifier {
-                        match get_variable(replacement.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                count = value;
-                                count_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
+    let code = match fs::read_to_string(path) {
+        Ok(code) => code,
+        Err(e) => {
+            print_error(format!(
+                "Error: Could not open file `{}`\nProblem: {}",
+                path.display(),
+                e
+            ));
+            return;
+        }
+    };
+
+    let (lexed_code, lexing_err) = lexer::lex(code);
+
+    if let Error::LexingError(err) = lexing_err {
+        print_error(located(err));
+    }
+
+    importing.push((
+        path.display().to_string(),
+        fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+    ));
+
+    let mut file_labels: Vec<(String, Vec<(usize, lexer::Line)>)> = Vec::new();
+    let mut namespaces: Vec<String> = Vec::new();
+    let mut current_label: String = "".to_string();
+    let mut label_code: Vec<(usize, lexer::Line)> = Vec::new();
+
+    for (line_number, line) in lexed_code.iter().enumerate() {
+        let line_number = line_number + 1;
+        let line: Vec<String> = line.clone().0;
+        if line.is_empty() {
+            continue;
+        }
+        let string_line = line.clone().join(" ");
+
+        let command: String = line[0].clone();
+        let args: Vec<String> = line[1..].to_vec().clone();
+        let args_len = args.len();
+
+        match command.clone().as_str() {
+            "label" => {
+                if args_len == 0 {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: Expected at least 1 argument, got 0.",
+                        line_number,
+                        string_line.clone()
+                    )));
+                } else {
+                    file_labels.push((current_label.clone(), label_code));
+                    label_code = Vec::new();
+
+                    for (index, parameter) in args[1..].iter().enumerate() {
+                        if get_type(parameter.clone()) != ast::Types::Identifier
+                            || args[1..index + 1].contains(parameter)
+                        {
+                            print_error(located(format!(
+                                "\nCode:\n{} | {}\nProblem: `{}` is not a valid parameter name or is used twice.",
+                                line_number,
+                                string_line.clone(),
+                                parameter
+                            )));
                         }
                     }
 
-                    if count_type != ast::Types::Number {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Replacement `{}` is not of type Number.",
-                            line_number,
-                            string_line.clone(),
-                            args[2].clone()
-                        ));
+                    // The parameters are kept as the first line of the label
+                    if args_len > 1 {
+                        label_code.push((line_number, lexer::Line(line.clone())));
                     }
 
-                    if let Ok(count) = count.parse::<usize>() {
-                        let re = Regex::new(&pattern).unwrap();
-                        let result = re.replacen(&item, count, &replacement);
+                    let label_name = args[0].clone();
+                    current_label = label_name.clone();
 
-                        variables.insert("TEMP".to_string(), result.to_string());
-                    } else {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: `{}` is supposed to be an integer less than 18446744073709551616.",
+                    if file_labels.iter().any(|(name, _)| *name == label_name) {
+                        print_error(located(format!(
+                            "\nCode:\n{} | {}\nProblem: Label `{}` already exists.",
                             line_number,
                             string_line.clone(),
-                            args[3].clone()
-                        ));
+                            label_name
+                        )));
                     }
                 }
             }
 
-            "replace_all" => {
-                if args_len != 3 {
-                    print_error(format!(
-                        "\nCode:\n{} | {}\nProblem: Expected 3 arguments, got {}.",
+            "import" => {
+                if !(args_len == 1 || (args_len == 3 && args[1] == "as")) {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: Expected `import <STRING>` or `import <STRING> as <NAMESPACE>`.",
+                        line_number,
+                        string_line.clone()
+                    )));
+                }
+
+                if get_type(args[0].clone()) != ast::Types::String {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: `{}` is of type `{}`, but `String` is required.",
                         line_number,
                         string_line.clone(),
-                        args_len
-                    ));
-                } else {
-                    let mut item = args[0].clone();
-                    let item_type = get_type(item.clone());
+                        args[0],
+                        get_type(args[0].clone())
+                    )));
+                }
 
-                    if item_type.clone() == ast::Types::Identifier {
-                        match get_variable(item.clone(), variables.clone()) {
-                            Ok((value, _)) => {
-                                item = value;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+                // Paths are relative to the script that imports them
+                let import_path = path
+                    .parent()
+                    .unwrap_or_else(|| Path::new(""))
+                    .join(get_string_content(args[0].clone()));
 
-                    if item_type.clone() != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Cannot check if `{}` is a match for `{}` as it is not a string.",
-                            line_number,
-                            string_line.clone(),
-                            item,
-                            args[1].clone()
-                        ));
-                    }
+                let import_namespace = if args_len == 3 {
+                    args[2].clone()
+                } else {
+                    import_path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                };
+
+                if get_type(import_namespace.clone()) != ast::Types::Identifier {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: `{}` cannot be used as a namespace, give another one with `as <NAMESPACE>`.",
+                        line_number,
+                        string_line.clone(),
+                        import_namespace
+                    )));
+                }
 
-                    let mut pattern = args[1].clone();
-                    let mut pattern_type = get_type(pattern.clone());
+                if !import_path.is_file() {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: Script `{}` does not exist.",
+                        line_number,
+                        string_line.clone(),
+                        import_path.display()
+                    )));
+                }
 
-                    if pattern_type == ast::Types::Identifier {
-                        match get_variable(pattern.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                pattern = value;
-                                pattern_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+                let canonical_path =
+                    fs::canonicalize(&import_path).unwrap_or_else(|_| import_path.clone());
 
-                    if pattern_type != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not of type String.",
-                            line_number,
-                            string_line.clone(),
-                            args[1].clone()
-                        ));
-                    }
+                if let Some(start) = importing.iter().position(|(_, p)| *p == canonical_path) {
+                    let cycle = importing[start..]
+                        .iter()
+                        .map(|(shown, _)| format!("`{}`", shown))
+                        .chain(std::iter::once(format!("`{}`", import_path.display())))
+                        .collect::<Vec<String>>()
+                        .join(" -> ");
 
-                    pattern = get_string_content(pattern.clone());
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: Scripts cannot import each other in a cycle: {}.",
+                        line_number,
+                        string_line.clone(),
+                        cycle
+                    )));
+                }
 
-                    let mut replacement = args[2].clone();
-                    let mut replacement_type = get_type(replacement.clone());
+                if namespaces.contains(&import_namespace) {
+                    print_error(located(format!(
+                        "\nCode:\n{} | {}\nProblem: Namespace `{}` is already used by another import.",
+                        line_number,
+                        string_line.clone(),
+                        import_namespace
+                    )));
+                }
 
-                    if replacement_type == ast::Types::Identifier {
-                        match get_variable(replacement.clone(), variables.clone()) {
-                            Ok((value, value_type)) => {
-                                replacement = value;
-                                replacement_type = value_type;
-                            }
-                            Err(e) => {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: {}",
-                                    line_number,
-                                    string_line.clone(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
+                load_script(
+                    &import_path,
+                    &(namespace.to_owned() + &import_namespace + "."),
+                    labels,
+                    importing,
+                );
+                namespaces.push(import_namespace);
+            }
 
-                    if replacement_type != ast::Types::String {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Replacement `{}` is not of type String.",
-                            line_number,
-                            string_line.clone(),
-                            args[2].clone()
-                        ));
-                    }
+            _ => {
+                label_code.push((line_number, lexer::Line(line.clone())));
+            }
+        }
+    }
+
+    file_labels.push((current_label, label_code));
+    importing.pop();
+
+    // The names this script can use for labels, which are its own and those of its imports
+    let mut known_labels: Vec<String> = file_labels.iter().map(|(name, _)| name.clone()).collect();
+    for name in labels.keys() {
+        if let Some(relative_name) = name.strip_prefix(namespace) {
+            if namespaces
+                .iter()
+                .any(|prefix| relative_name.starts_with(&(prefix.to_owned() + ".")))
+            {
+                known_labels.push(relative_name.to_string());
+            }
+        }
+    }
 
-                    replacement = get_string_content(replacement);
+    for (label_name, mut code) in file_labels {
+        // Only the script that is run has an entry point and code before its first label
+        if !namespace.is_empty() && (label_name.is_empty() || label_name == ".ENTRY") {
+            continue;
+        }
 
-                    if let Ok(re) = Regex::new(&pattern) {
-                        variables.insert(
-                            "TEMP".to_string(),
-                            re.replace_all(&item, &replacement).to_string(),
-                        );
-                    } else {
-                        print_error(format!(
-                            "\nCode:\n{} | {}\nProblem: Pattern `{}` is not a valid regular expression.",
-                            line_number,
-                            string_line.clone(),
-                            args[1].clone()
-                        ));
+        // Jumps in an imported script go to the labels of that script
+        if !namespace.is_empty() {
+            for (_, line) in code.iter_mut() {
+                if let Some(index) = label_argument(&line.0[0]) {
+                    if let Some(target) = line.0.get_mut(index + 1) {
+                        if known_labels.contains(target) {
+                            *target = namespace.to_owned() + target;
+                        }
                     }
                 }
             }
+        }
 
-            "comment" => {
-                // Do nothing
-            }
+        let full_name = namespace.to_owned() + &label_name;
 
-            _ => {
-                print_error(format!(
-                    "\nCode:\n{} | {}\nProblem: Unknown command `{}`.",
-                    line_number,
-                    string_line.clone(),
-                    command.clone()
-                ));
-            }
+        if labels.contains_key(&full_name) {
+            print_error(format!(
+                "\nError: Could not load `{}`\nProblem: Label `{}` already exists.",
+                path.display(),
+                full_name
+            ));
+        }
+
+        if !namespace.is_empty() {
+            errors::set_label_file(&full_name, &path.display().to_string());
+            scopes::set_namespace(&full_name, namespace);
         }
+
+        labels.insert(full_name, code);
     }
 }
 
@@ -1638,86 +2516,36 @@ fn main() {
     args.next().unwrap();
 
     match args.next() {
-        Some(input_file) => match fs::read_to_string(input_file.clone()) {
-            Ok(code) => {
-                let (lexed_code, lexing_err) = lexer::lex(code);
-
-                if lexing_err != Error::None {
-                    if let Error::LexingError(err) = lexing_err {
-                        print_error(err);
-                    }
-                }
-
-                let mut variables: HashMap<String, String> = HashMap::new();
-                let mut labels: HashMap<String, Vec<(usize, lexer::Line)>> = HashMap::new();
-                let mut current_label: String = "".to_string();
-                let mut label_code: Vec<(usize, lexer::Line)> = Vec::new();
-
-                for (line_number, line) in lexed_code.iter().enumerate() {
-                    let line_number = line_number + 1;
-                    let line: Vec<String> = line.clone().0;
-                    if line.is_empty() {
-                        continue;
-                    }
-                    let string_line = line.clone().join(" ");
+        Some(input_file) => {
+            let mut variables: HashMap<String, String> = HashMap::new();
+            let mut labels: HashMap<String, Vec<(usize, lexer::Line)>> = HashMap::new();
 
-                    let command: String = line[0].clone();
-                    let args: Vec<String> = line[1..].to_vec().clone();
-                    let args_len = args.len();
-
-                    match command.clone().as_str() {
-                        "label" => {
-                            if args_len != 1 {
-                                print_error(format!(
-                                    "\nCode:\n{} | {}\nProblem: Expected 1 argument, got {}.",
-                                    line_number,
-                                    string_line.clone(),
-                                    args_len
-                                ));
-                            } else {
-                                labels.insert(current_label.clone(), label_code);
-                                label_code = Vec::new();
-
-                                let label_name = args[0].clone();
-                                current_label = label_name.clone();
-
-                                if labels.contains_key(&label_name) {
-                                    print_error(format!(
-                                        "\nCode:\n{} | {}\nProblem: Label `{}` already exists.",
-                                        line_number,
-                                        string_line.clone(),
-                                        label_name
-                                    ));
-                                }
-                            }
-                        }
+            load_script(Path::new(&input_file), "", &mut labels, &mut Vec::new());
 
-                        _ => {
-                            label_code.push((line_number, lexer::Line(line.clone())));
-                        }
-                    }
+            if let Err((label_name, line_number, string_line, e)) =
+                constants::check_labels(&labels).and_then(|_| check_jumps(&labels))
+            {
+                errors::enter_label(&label_name);
+                print_error(format!(
+                    "\nCode:\n{} | {}\nProblem: {}",
+                    line_number, string_line, e
+                ));
+            }
 
-                    if line_number == lexed_code.len() {
-                        labels.insert(current_label.clone(), label_code);
-                        label_code = Vec::new();
-                    }
-                }
+            constants::define_predefined(Path::new(&input_file), &mut variables);
 
-                if labels.clone().contains_key(".ENTRY") {
-                    let entry_code = labels.get(".ENTRY").unwrap().clone();
-                    interpret(entry_code, &mut variables, &mut labels);
-                } else {
+            if labels.clone().contains_key(".ENTRY") {
+                let entry_code = labels.get(".ENTRY").unwrap().clone();
+                if run_label(".ENTRY", entry_code, &mut variables, &mut labels) != ast::Flow::Next {
                     print_error(
-                        "\nError: Could not execute\nProblem: No `.ENTRY` label.".to_string(),
+                        "\nError: Could not execute\nProblem: `break` or `continue` was used outside of `foreach`."
+                            .to_string(),
                     );
                 }
+            } else {
+                print_error("\nError: Could not execute\nProblem: No `.ENTRY` label.".to_string());
             }
-
-            Err(e) => print_error(format!(
-                "Error: Could not open file `{}`\nProblem: {}",
-                input_file, e
-            )),
-        },
+        }
 
         None => {
             println!("Usage: script-ll <source_code>.ll\nExample: script-ll examples/tutorial.ll");
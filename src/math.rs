@@ -0,0 +1,156 @@
+use crate::ast;
+use crate::errors::Error;
+use std::collections::HashMap;
+
+fn resolve_number(value: &str, variables: &HashMap<String, String>) -> Result<f64, Error> {
+    let (resolved, resolved_type) = crate::resolve_value(value.to_string(), variables)?;
+
+    if resolved_type != ast::Types::Number {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `Number` is required.",
+            value, resolved_type
+        )));
+    }
+
+    Ok(resolved.parse::<f64>().unwrap())
+}
+
+fn resolve_numbers(
+    args: &[String],
+    variables: &HashMap<String, String>,
+) -> Result<Vec<f64>, Error> {
+    args.iter()
+        .map(|arg| resolve_number(arg, variables))
+        .collect()
+}
+
+/// The largest number up to which every whole number can be stored exactly, which is 2^53.
+pub(crate) const MAX_SAFE_INTEGER: f64 = 9007199254740992.0;
+
+/// Checks that `result`, the result of `command`, can be stored. Numbers are always finite, so
+/// `inf` and `NaN` cannot be.
+pub fn check_finite(command: &str, result: f64) -> Result<(), Error> {
+    if !result.is_finite() {
+        return Err(Error::RuntimeError(format!(
+            "The result of `{}` is not a finite number.",
+            command
+        )));
+    }
+
+    Ok(())
+}
+
+/// Runs one of the math commands and stores its result to `TEMP`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    let result = match command {
+        "pi" | "e" => {
+            crate::expect_args(args, 0)?;
+
+            if command == "pi" {
+                std::f64::consts::PI
+            } else {
+                std::f64::consts::E
+            }
+        }
+
+        "sqrt" | "abs" | "floor" | "ceil" | "sin" | "cos" | "tan" | "ln" | "log10" => {
+            crate::expect_args(args, 1)?;
+            let number = resolve_number(&args[0], variables)?;
+
+            if number < 0.0 && command == "sqrt" {
+                return Err(Error::RuntimeError(format!(
+                    "Cannot get the square root of `{}` as it is negative.",
+                    args[0]
+                )));
+            }
+
+            if number <= 0.0 && (command == "ln" || command == "log10") {
+                return Err(Error::RuntimeError(format!(
+                    "Cannot get the logarithm of `{}` as it is not positive.",
+                    args[0]
+                )));
+            }
+
+            match command {
+                "sqrt" => number.sqrt(),
+                "abs" => number.abs(),
+                "floor" => number.floor(),
+                "ceil" => number.ceil(),
+                "sin" => number.sin(),
+                "cos" => number.cos(),
+                "tan" => number.tan(),
+                "ln" => number.ln(),
+                _ => number.log10(),
+            }
+        }
+
+        "round" => {
+            if args.len() != 1 && args.len() != 2 {
+                return Err(Error::RuntimeError(format!(
+                    "Expected 1 or 2 arguments, got {}.",
+                    args.len()
+                )));
+            }
+            let numbers = resolve_numbers(args, variables)?;
+            let digits = if numbers.len() == 2 { numbers[1] } else { 0.0 };
+
+            if digits < 0.0 || digits.fract() != 0.0 {
+                return Err(Error::RuntimeError(format!(
+                    "`{}` is supposed to be a whole number of digits.",
+                    args[1]
+                )));
+            }
+
+            let scale = 10f64.powf(digits);
+            (numbers[0] * scale).round() / scale
+        }
+
+        "pow" => {
+            crate::expect_args(args, 2)?;
+            let numbers = resolve_numbers(args, variables)?;
+
+            numbers[0].powf(numbers[1])
+        }
+
+        "min" | "max" => {
+            if args.is_empty() {
+                return Err(Error::RuntimeError(
+                    "Expected at least 1 argument, got 0.".to_string(),
+                ));
+            }
+            let numbers = resolve_numbers(args, variables)?;
+
+            if command == "min" {
+                numbers.into_iter().fold(f64::INFINITY, f64::min)
+            } else {
+                numbers.into_iter().fold(f64::NEG_INFINITY, f64::max)
+            }
+        }
+
+        "clamp" => {
+            crate::expect_args(args, 3)?;
+            let numbers = resolve_numbers(args, variables)?;
+
+            if numbers[1] > numbers[2] {
+                return Err(Error::RuntimeError(format!(
+                    "Cannot clamp as the minimum `{}` is greater than the maximum `{}`.",
+                    args[1], args[2]
+                )));
+            }
+
+            numbers[0].max(numbers[1]).min(numbers[2])
+        }
+
+        _ => unreachable!(),
+    };
+
+    check_finite(command, result)?;
+
+    variables.insert("TEMP".to_string(), format!("{}", result));
+
+    Ok(())
+}
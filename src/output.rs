@@ -0,0 +1,100 @@
+use crate::ast;
+use crate::errors::Error;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+
+// The files that output is redirected to by `redirect_output`, the last one being the one in use.
+// Redirections can be nested, `end_redirect` goes back to the previous one.
+thread_local! {
+    static REDIRECTS: RefCell<Vec<(String, File)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Writes `text` to the file that output is redirected to, or to stdout if there is none.
+pub fn write(text: &str) -> Result<(), Error> {
+    REDIRECTS.with(|redirects| match redirects.borrow_mut().last_mut() {
+        Some((path, file)) => file
+            .write_all(text.as_bytes())
+            .map_err(|e| Error::RuntimeError(format!("Failed to write to file `{}`: {}", path, e))),
+
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    })
+}
+
+/// Runs `eprint`, `eprintln`, `redirect_output` or `end_redirect`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    match command {
+        "eprint" => {
+            crate::expect_args(args, 1)?;
+            let (value, _) = crate::resolve_value(args[0].clone(), variables)?;
+
+            eprint!("{}", crate::format::to_text(&value));
+        }
+
+        "eprintln" => {
+            if args.len() > 1 {
+                return Err(Error::RuntimeError(format!(
+                    "Expected 0 or 1 arguments, got {}.",
+                    args.len()
+                )));
+            }
+
+            match args.first() {
+                Some(arg) => {
+                    let (value, _) = crate::resolve_value(arg.clone(), variables)?;
+                    eprintln!("{}", crate::format::to_text(&value));
+                }
+                None => eprintln!(),
+            }
+        }
+
+        "redirect_output" => {
+            crate::expect_args(args, 1)?;
+            let (path, path_type) = crate::resolve_value(args[0].clone(), variables)?;
+
+            if path_type != ast::Types::String {
+                return Err(Error::RuntimeError(format!(
+                    "`{}` is of type `{}`, but `String` is required.",
+                    args[0], path_type
+                )));
+            }
+
+            let path = crate::get_string_content(path);
+
+            match File::create(&path) {
+                Ok(file) => REDIRECTS.with(|redirects| redirects.borrow_mut().push((path, file))),
+                Err(e) => {
+                    return Err(Error::RuntimeError(format!(
+                        "Failed to create file `{}`: {}",
+                        path, e
+                    )))
+                }
+            }
+        }
+
+        "end_redirect" => {
+            crate::expect_args(args, 0)?;
+
+            if REDIRECTS
+                .with(|redirects| redirects.borrow_mut().pop())
+                .is_none()
+            {
+                return Err(Error::RuntimeError(
+                    "There is no `redirect_output` to end.".to_string(),
+                ));
+            }
+        }
+
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::errors::Error;
+use crate::{ast, collections, math};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// A SplitMix64 generator, which is small and good enough for scripts.
+// It is seeded from the clock unless `random_seed` is used.
+thread_local! {
+    static STATE: Cell<u64> = Cell::new(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0),
+    );
+}
+
+fn next_u64() -> u64 {
+    STATE.with(|state| {
+        let next = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state.set(next);
+
+        let mut z = next;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+/// Returns a number from `0` up to, but not including, `bound`.
+fn below(bound: u64) -> u64 {
+    ((next_u64() as u128 * bound as u128) >> 64) as u64
+}
+
+fn resolve_integer(value: &str, variables: &HashMap<String, String>) -> Result<i64, Error> {
+    let (resolved, resolved_type) = crate::resolve_value(value.to_string(), variables)?;
+
+    if resolved_type != ast::Types::Number {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `Number` is required.",
+            value, resolved_type
+        )));
+    }
+
+    let number = resolved.parse::<f64>().unwrap();
+
+    if number.fract() != 0.0 || number.abs() > math::MAX_SAFE_INTEGER {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is supposed to be a whole number.",
+            value
+        )));
+    }
+
+    Ok(number as i64)
+}
+
+fn resolve_list(value: &str, variables: &HashMap<String, String>) -> Result<Vec<String>, Error> {
+    let (resolved, resolved_type) = crate::resolve_value(value.to_string(), variables)?;
+
+    if resolved_type != ast::Types::List {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `List` is required.",
+            value, resolved_type
+        )));
+    }
+
+    collections::parse_list(&resolved)
+}
+
+/// Runs one of the random commands.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    match command {
+        "random_seed" => {
+            crate::expect_args(args, 1)?;
+            let seed = resolve_integer(&args[0], variables)?;
+
+            STATE.with(|state| state.set(seed as u64));
+        }
+
+        "random_int" => {
+            crate::expect_args(args, 2)?;
+            let min = resolve_integer(&args[0], variables)?;
+            let max = resolve_integer(&args[1], variables)?;
+
+            if min > max {
+                return Err(Error::RuntimeError(format!(
+                    "Cannot pick a number as the minimum `{}` is greater than the maximum `{}`.",
+                    args[0], args[1]
+                )));
+            }
+
+            let number = min + below((max - min) as u64 + 1) as i64;
+            variables.insert("TEMP".to_string(), number.to_string());
+        }
+
+        "random_float" => {
+            crate::expect_args(args, 0)?;
+            let number = (next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+
+            variables.insert("TEMP".to_string(), format!("{}", number));
+        }
+
+        "random_choice" => {
+            crate::expect_args(args, 1)?;
+            let items = resolve_list(&args[0], variables)?;
+
+            if items.is_empty() {
+                return Err(Error::RuntimeError(format!(
+                    "Cannot pick an item from `{}` as it is empty.",
+                    args[0]
+                )));
+            }
+
+            let item = items[below(items.len() as u64) as usize].clone();
+            variables.insert("TEMP".to_string(), item);
+        }
+
+        "shuffle" => {
+            crate::expect_args(args, 1)?;
+            let mut items = resolve_list(&args[0], variables)?;
+
+            for index in (1..items.len()).rev() {
+                items.swap(index, below(index as u64 + 1) as usize);
+            }
+
+            variables.insert("TEMP".to_string(), collections::make_list(&items));
+        }
+
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::errors::Error;
+use crate::strings::{resolve_string, to_string_value};
+use crate::{ast, collections};
+use regex::{Regex, RegexBuilder};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// Compiled patterns are kept around so that a pattern used in a loop is only compiled once.
+thread_local! {
+    static CACHE: RefCell<HashMap<(String, String), Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Compiles `pattern` with `flags`, reusing the result of an earlier compilation of the same
+/// pattern and flags.
+///
+/// The flags are `i` (case-insensitive), `m` (`^` and `$` match at every line), `s` (`.` matches
+/// newlines) and `x` (whitespace and `#` comments in the pattern are ignored).
+pub fn compile_with_flags(pattern: &str, flags: &str) -> Result<Regex, Error> {
+    let key = (pattern.to_string(), flags.to_string());
+
+    if let Some(re) = CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+        return Ok(re);
+    }
+
+    let mut builder = RegexBuilder::new(pattern);
+
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'x' => builder.ignore_whitespace(true),
+            _ => {
+                return Err(Error::RuntimeError(format!(
+                    "Unknown regex flag `{}`, expected any of `i`, `m`, `s` and `x`.",
+                    flag
+                )))
+            }
+        };
+    }
+
+    match builder.build() {
+        Ok(re) => {
+            CACHE.with(|cache| cache.borrow_mut().insert(key, re.clone()));
+            Ok(re)
+        }
+
+        // The message of a syntax error shows the pattern with a `^` under the problem
+        Err(regex::Error::Syntax(message)) => Err(Error::RuntimeError(format!(
+            "Pattern `{}` is not a valid regular expression.\n{}",
+            pattern,
+            message.trim_start_matches("regex parse error:\n")
+        ))),
+
+        Err(e) => Err(Error::RuntimeError(format!(
+            "Pattern `{}` is not a valid regular expression: {}",
+            pattern, e
+        ))),
+    }
+}
+
+/// Compiles `pattern` without any flags.
+pub fn compile(pattern: &str) -> Result<Regex, Error> {
+    compile_with_flags(pattern, "")
+}
+
+/// Compiles the pattern `pattern` with the flags `flags`, resolving both if they are variables.
+pub fn resolve_regex(
+    pattern: &str,
+    flags: Option<&String>,
+    variables: &HashMap<String, String>,
+) -> Result<Regex, Error> {
+    let flags = match flags {
+        Some(flags) => resolve_string(flags, variables)?,
+        None => String::new(),
+    };
+
+    compile_with_flags(&resolve_string(pattern, variables)?, &flags)
+}
+
+/// Runs one of the regex commands and stores the result to `TEMP`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    // Every command can be given flags after its usual arguments
+    let expected = match command {
+        "replace_all" => 3,
+        "replace_n" => 4,
+        _ => 2,
+    };
+
+    if args.len() != expected && args.len() != expected + 1 {
+        return Err(Error::RuntimeError(format!(
+            "Expected {} or {} arguments, got {}.",
+            expected,
+            expected + 1,
+            args.len()
+        )));
+    }
+
+    let string = resolve_string(&args[0], variables)?;
+    let re = resolve_regex(&args[1], args.get(expected), variables)?;
+
+    let result = match command {
+        "is_match" => re.is_match(&string).to_string(),
+
+        "count_matches" => re.find_iter(&string).count().to_string(),
+
+        "replace_all" | "replace_n" => {
+            let replacement = resolve_string(&args[2], variables)?;
+
+            if command == "replace_all" {
+                to_string_value(&re.replace_all(&string, replacement.as_str()))
+            } else {
+                let (count, count_type) = crate::resolve_value(args[3].clone(), variables)?;
+
+                match count.parse::<usize>() {
+                    Ok(count) if count_type == ast::Types::Number => {
+                        to_string_value(&re.replacen(&string, count, replacement.as_str()))
+                    }
+                    _ => {
+                        return Err(Error::RuntimeError(format!(
+                            "`{}` is supposed to be a whole number that is not negative.",
+                            args[3]
+                        )))
+                    }
+                }
+            }
+        }
+
+        // An empty string is stored when nothing matches
+        "find_first" => to_string_value(re.find(&string).map_or("", |m| m.as_str())),
+
+        "find_all" => collections::make_list(
+            &re.find_iter(&string)
+                .map(|m| to_string_value(m.as_str()))
+                .collect::<Vec<String>>(),
+        ),
+
+        // Groups are stored by number, and also by name if they have one. Groups that did not
+        // take part in the match are empty strings. An empty map is stored when nothing matches.
+        "captures" => {
+            let mut entries: Vec<(String, String)> = Vec::new();
+
+            if let Some(captures) = re.captures(&string) {
+                for (index, name) in re.capture_names().enumerate() {
+                    let value = to_string_value(captures.get(index).map_or("", |m| m.as_str()));
+
+                    entries.push((index.to_string(), value.clone()));
+
+                    if let Some(name) = name {
+                        entries.push((to_string_value(name), value));
+                    }
+                }
+            }
+
+            collections::make_map(&entries)
+        }
+
+        _ => unreachable!(),
+    };
+
+    variables.insert("TEMP".to_string(), result);
+
+    Ok(())
+}
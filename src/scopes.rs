@@ -0,0 +1,188 @@
+use crate::ast;
+use crate::errors::Error;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// The variables declared with `local` in a running label along with the values they had before,
+// which are put back when the label returns. `None` means that the variable did not exist.
+type Frame = Vec<(String, Option<String>)>;
+
+// One frame for every label that is running, the last one being the innermost, along with whether
+// the label was run with `call` and the namespace of the script it is in.
+thread_local! {
+    static FRAMES: RefCell<Vec<(bool, String, Frame)>> = const { RefCell::new(Vec::new()) };
+}
+
+// The namespace of every label of an imported script, such as `util.` for `util.greet`.
+thread_local! {
+    static NAMESPACES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+// The value given to the last `return`, which is what the label run with `call` returns.
+thread_local! {
+    static RETURNED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Records the value that the label running now returns, `None` if it returns none.
+pub fn set_returned(value: Option<String>) {
+    RETURNED.with(|returned| *returned.borrow_mut() = value);
+}
+
+/// Takes the value returned by the label that was called last.
+pub fn take_returned() -> Option<String> {
+    RETURNED.with(|returned| returned.borrow_mut().take())
+}
+
+/// Records that label `label_name` is in the imported script with namespace `namespace`.
+pub fn set_namespace(label_name: &str, namespace: &str) {
+    NAMESPACES.with(|namespaces| {
+        namespaces
+            .borrow_mut()
+            .insert(label_name.to_string(), namespace.to_string())
+    });
+}
+
+/// The namespace of the script that the label running now is in, which is empty for the script
+/// that is run.
+pub fn namespace() -> String {
+    FRAMES.with(|frames| {
+        frames
+            .borrow()
+            .last()
+            .map(|(_, namespace, _)| namespace.clone())
+            .unwrap_or_default()
+    })
+}
+
+/// Starts a frame for label `label_name` that is about to run, `is_call` being whether it is run
+/// with `call`.
+pub fn enter(label_name: &str, is_call: bool) {
+    let namespace = NAMESPACES.with(|namespaces| {
+        namespaces
+            .borrow()
+            .get(label_name)
+            .cloned()
+            .unwrap_or_default()
+    });
+
+    FRAMES.with(|frames| frames.borrow_mut().push((is_call, namespace, Vec::new())));
+}
+
+/// Ends the frame of a label that has returned, putting back the variables its locals hid.
+pub fn leave(variables: &mut HashMap<String, String>) {
+    let (_, _, frame) = FRAMES
+        .with(|frames| frames.borrow_mut().pop())
+        .unwrap_or_default();
+
+    for (name, previous) in frame.into_iter().rev() {
+        match previous {
+            Some(value) => variables.insert(name, value),
+            None => variables.remove(&name),
+        };
+    }
+}
+
+/// Whether any of the labels running now was run with `call`.
+pub fn in_call() -> bool {
+    FRAMES.with(|frames| frames.borrow().iter().any(|(is_call, _, _)| *is_call))
+}
+
+/// Creates a variable that only lasts until the label running now returns.
+pub fn declare(name: String, value: String, variables: &mut HashMap<String, String>) {
+    FRAMES.with(|frames| {
+        if let Some((_, _, frame)) = frames.borrow_mut().last_mut() {
+            // Only the value from before the first `local` is put back
+            if !frame.iter().any(|(local, _)| *local == name) {
+                frame.push((name.clone(), variables.get(&name).cloned()));
+            }
+        }
+    });
+
+    variables.insert(name, value);
+}
+
+fn check_name(name: &str) -> Result<(), Error> {
+    if crate::get_type(name.to_string()) != ast::Types::Identifier {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is not a valid variable name.",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Runs `local` or `global`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    match command {
+        "local" => {
+            crate::expect_args(args, 2)?;
+            check_name(&args[0])?;
+            let (value, _) = crate::resolve_value(args[1].clone(), variables)?;
+
+            declare(args[0].clone(), value, variables);
+        }
+
+        // The global value of a variable is the one from before the outermost `local` hiding it
+        "global" => {
+            if args.len() != 1 && args.len() != 2 {
+                return Err(Error::RuntimeError(format!(
+                    "Expected 1 or 2 arguments, got {}.",
+                    args.len()
+                )));
+            }
+            check_name(&args[0])?;
+            let name = args[0].clone();
+
+            let hidden = FRAMES.with(|frames| {
+                frames.borrow().iter().find_map(|(_, _, frame)| {
+                    frame
+                        .iter()
+                        .find(|(local, _)| *local == name)
+                        .map(|(_, previous)| previous.clone())
+                })
+            });
+
+            if args.len() == 1 {
+                let value = match hidden {
+                    Some(previous) => previous,
+                    None => variables.get(&name).cloned(),
+                };
+
+                match value {
+                    Some(value) => variables.insert("TEMP".to_string(), value),
+                    None => {
+                        return Err(Error::RuntimeError(format!(
+                            "Variable `{}` does not exist.",
+                            name
+                        )))
+                    }
+                };
+            } else {
+                let (value, _) = crate::resolve_value(args[1].clone(), variables)?;
+
+                if hidden.is_some() {
+                    FRAMES.with(|frames| {
+                        for (_, _, frame) in frames.borrow_mut().iter_mut() {
+                            if let Some(entry) = frame.iter_mut().find(|(local, _)| *local == name)
+                            {
+                                entry.1 = Some(value);
+                                break;
+                            }
+                        }
+                    });
+                } else {
+                    variables.insert(name, value);
+                }
+            }
+        }
+
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
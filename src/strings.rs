@@ -0,0 +1,280 @@
+use crate::errors::Error;
+use crate::{ast, collections, regexes};
+use std::collections::HashMap;
+
+// Every position and length here counts characters, not bytes, so `"é"` has a length of 1.
+
+/// Returns the content of the string `value`, resolving it if it is a variable.
+pub(crate) fn resolve_string(
+    value: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, Error> {
+    let (resolved, resolved_type) = crate::resolve_value(value.to_string(), variables)?;
+
+    if resolved_type != ast::Types::String {
+        return Err(Error::RuntimeError(format!(
+            "`{}` is of type `{}`, but `String` is required.",
+            value, resolved_type
+        )));
+    }
+
+    Ok(crate::get_string_content(resolved))
+}
+
+fn resolve_count(value: &str, variables: &HashMap<String, String>) -> Result<usize, Error> {
+    let (resolved, resolved_type) = crate::resolve_value(value.to_string(), variables)?;
+
+    match resolved.parse::<usize>() {
+        Ok(count) if resolved_type == ast::Types::Number => Ok(count),
+        _ => Err(Error::RuntimeError(format!(
+            "`{}` is supposed to be a whole number that is not negative.",
+            value
+        ))),
+    }
+}
+
+fn expect_args_between(args: &[String], min: usize, max: usize) -> Result<(), Error> {
+    if args.len() < min || args.len() > max {
+        Err(Error::RuntimeError(format!(
+            "Expected {} to {} arguments, got {}.",
+            min,
+            max,
+            args.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// The longest string, in bytes, that `repeat`, `pad_left`, `pad_right` and format specifiers can
+/// make.
+pub(crate) const MAX_LENGTH: usize = 1 << 30;
+
+// Repeats `string` `count` times, unless the result would be longer than `MAX_LENGTH`.
+fn repeat_string(string: &str, count: usize) -> Result<String, Error> {
+    match string.len().checked_mul(count) {
+        Some(length) if length <= MAX_LENGTH => Ok(string.repeat(count)),
+        _ => Err(Error::RuntimeError(format!(
+            "The result would be longer than {} bytes.",
+            MAX_LENGTH
+        ))),
+    }
+}
+
+/// Makes a value of type String with the content `content`.
+pub(crate) fn to_string_value(content: &str) -> String {
+    "\"".to_owned() + content + "\""
+}
+
+fn to_string_list<'a>(parts: impl Iterator<Item = &'a str>) -> String {
+    collections::make_list(&parts.map(to_string_value).collect::<Vec<String>>())
+}
+
+/// Runs one of the string commands and stores its result to `TEMP`.
+pub fn command(
+    command: &str,
+    args: &[String],
+    variables: &mut HashMap<String, String>,
+) -> Result<(), Error> {
+    let result = match command {
+        "len" => {
+            crate::expect_args(args, 1)?;
+            let (value, value_type) = crate::resolve_value(args[0].clone(), variables)?;
+
+            match value_type {
+                ast::Types::String => crate::get_string_content(value).chars().count(),
+                ast::Types::List => collections::parse_list(&value)?.len(),
+                ast::Types::Map => collections::parse_map(&value)?.len(),
+                _ => {
+                    return Err(Error::RuntimeError(format!(
+                        "`{}` is of type `{}`, but `String`, `List` or `Map` is required.",
+                        args[0], value_type
+                    )))
+                }
+            }
+            .to_string()
+        }
+
+        "substr" => {
+            expect_args_between(args, 2, 3)?;
+            let characters = resolve_string(&args[0], variables)?
+                .chars()
+                .collect::<Vec<char>>();
+            let start = resolve_count(&args[1], variables)?;
+
+            if start > characters.len() {
+                return Err(Error::RuntimeError(format!(
+                    "`{}` is past the end of `{}`.",
+                    args[1], args[0]
+                )));
+            }
+
+            let end = if args.len() == 3 {
+                characters
+                    .len()
+                    .min(start.saturating_add(resolve_count(&args[2], variables)?))
+            } else {
+                characters.len()
+            };
+
+            to_string_value(&characters[start..end].iter().collect::<String>())
+        }
+
+        "index_of" => {
+            crate::expect_args(args, 2)?;
+            let string = resolve_string(&args[0], variables)?;
+            let pattern = resolve_string(&args[1], variables)?;
+
+            match string.find(&pattern) {
+                Some(byte_index) => string[..byte_index].chars().count().to_string(),
+                None => "-1".to_string(),
+            }
+        }
+
+        "contains" | "starts_with" | "ends_with" => {
+            crate::expect_args(args, 2)?;
+            let string = resolve_string(&args[0], variables)?;
+            let pattern = resolve_string(&args[1], variables)?;
+
+            match command {
+                "contains" => string.contains(&pattern),
+                "starts_with" => string.starts_with(&pattern),
+                _ => string.ends_with(&pattern),
+            }
+            .to_string()
+        }
+
+        "upper" | "lower" | "trim" | "trim_start" | "trim_end" | "reverse" => {
+            crate::expect_args(args, 1)?;
+            let string = resolve_string(&args[0], variables)?;
+
+            to_string_value(&match command {
+                "upper" => string.to_uppercase(),
+                "lower" => string.to_lowercase(),
+                "trim" => string.trim().to_string(),
+                "trim_start" => string.trim_start().to_string(),
+                "trim_end" => string.trim_end().to_string(),
+                _ => string.chars().rev().collect::<String>(),
+            })
+        }
+
+        "repeat" => {
+            crate::expect_args(args, 2)?;
+            let string = resolve_string(&args[0], variables)?;
+            let count = resolve_count(&args[1], variables)?;
+
+            to_string_value(&repeat_string(&string, count)?)
+        }
+
+        "pad_left" | "pad_right" => {
+            expect_args_between(args, 2, 3)?;
+            let string = resolve_string(&args[0], variables)?;
+            let width = resolve_count(&args[1], variables)?;
+            let padding = if args.len() == 3 {
+                resolve_string(&args[2], variables)?
+            } else {
+                " ".to_string()
+            };
+
+            if padding.chars().count() != 1 {
+                return Err(Error::RuntimeError(format!(
+                    "Padding `{}` is supposed to be a single character.",
+                    args[2]
+                )));
+            }
+
+            let padding = repeat_string(&padding, width.saturating_sub(string.chars().count()))?;
+
+            if command == "pad_left" {
+                to_string_value(&(padding + &string))
+            } else {
+                to_string_value(&(string + &padding))
+            }
+        }
+
+        "char_at" => {
+            crate::expect_args(args, 2)?;
+            let string = resolve_string(&args[0], variables)?;
+            let index = resolve_count(&args[1], variables)?;
+
+            match string.chars().nth(index) {
+                Some(c) => to_string_value(&c.to_string()),
+                None => {
+                    return Err(Error::RuntimeError(format!(
+                        "`{}` is past the end of `{}`.",
+                        args[1], args[0]
+                    )))
+                }
+            }
+        }
+
+        "split" => {
+            crate::expect_args(args, 2)?;
+            let string = resolve_string(&args[0], variables)?;
+            let separator = resolve_string(&args[1], variables)?;
+
+            if separator.is_empty() {
+                collections::make_list(
+                    &string
+                        .chars()
+                        .map(|c| to_string_value(&c.to_string()))
+                        .collect::<Vec<String>>(),
+                )
+            } else {
+                to_string_list(string.split(separator.as_str()))
+            }
+        }
+
+        "split_regex" => {
+            expect_args_between(args, 2, 3)?;
+            let string = resolve_string(&args[0], variables)?;
+            let re = regexes::resolve_regex(&args[1], args.get(2), variables)?;
+
+            to_string_list(re.split(&string))
+        }
+
+        "lines" | "split_whitespace" => {
+            crate::expect_args(args, 1)?;
+            let string = resolve_string(&args[0], variables)?;
+
+            if command == "lines" {
+                to_string_list(string.lines())
+            } else {
+                to_string_list(string.split_whitespace())
+            }
+        }
+
+        "join" => {
+            crate::expect_args(args, 2)?;
+            let (list, list_type) = crate::resolve_value(args[0].clone(), variables)?;
+            let separator = resolve_string(&args[1], variables)?;
+
+            if list_type != ast::Types::List {
+                return Err(Error::RuntimeError(format!(
+                    "`{}` is of type `{}`, but `List` is required.",
+                    args[0], list_type
+                )));
+            }
+
+            to_string_value(
+                &collections::parse_list(&list)?
+                    .into_iter()
+                    .map(|item| {
+                        if crate::get_type(item.clone()) == ast::Types::String {
+                            crate::get_string_content(item)
+                        } else {
+                            item
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(&separator),
+            )
+        }
+
+        _ => unreachable!(),
+    };
+
+    variables.insert("TEMP".to_string(), result);
+
+    Ok(())
+}